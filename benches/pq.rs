@@ -20,7 +20,7 @@ fn bench_push_skippy(b: &mut Bencher) {
             seed ^= seed >> 17;
             seed ^= seed << 7;
 
-            queue.push(seed);
+            queue.push(seed, ());
         }
     });
 
@@ -79,7 +79,7 @@ fn bench_push_pop_skippy(b: &mut Bencher) {
 
             match seed % 5 {
                 0 => {
-                    queue.push(seed);
+                    queue.push(seed, ());
                 }
                 _ => {
                     queue.pop();
@@ -181,7 +181,7 @@ fn bench_push_skippy_threaded(b: &mut Bencher) {
                     let mut rng = rand::thread_rng();
                     for _ in 0..n {
                         let target = rng.gen::<u32>();
-                        queue.push(target);
+                        queue.push(target, ());
                     }
                 })
             })
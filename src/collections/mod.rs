@@ -1 +1,2 @@
 pub mod priority_queue;
+pub mod set;
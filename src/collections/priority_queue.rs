@@ -1,3 +1,15 @@
+use core::cmp::Ordering as CmpOrdering;
+use core::fmt;
+use core::future::Future;
+use core::pin::Pin;
+use core::sync::atomic::{fence, AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use core::task::{Context, Poll, Waker};
+use core::time::Duration;
+use std::collections::VecDeque;
+use std::sync::{Condvar, Mutex};
+use std::time::Instant;
+
+use crate::comparator::{Comparator, OrdComparator, ReverseComparator};
 use crate::internal::skiplist;
 use crate::internal::skiplist::SkipList;
 use crate::internal::sync;
@@ -5,41 +17,259 @@ use crate::internal::sync::SkipList as SyncSkipList;
 
 /// [PriorityQueue](PriorityQueue) is implemented using a [SkipList](crate::skiplist::SkipList) and is available as both
 /// a non-thread safe, but faster, and a thread-safe, yet slower, variation.
-pub struct PriorityQueue<L> {
-    queue: L,
+///
+/// Items are ordered by a separate priority, lowest first unless the queue was made by
+/// [new_max](PriorityQueue::new_max), so the items themselves need not be orderable. Items of
+/// equal priority are popped in the order they were pushed.
+///
+/// Both ends of the queue can be popped: [pop_max](PriorityQueue::pop_max) takes the item that
+/// [pop](PriorityQueue::pop) would return last.
+///
+/// Its [Flavour] decides whether the queue is thread-safe: a [Local] queue is faster, while a
+/// [Sync](flavour::Sync) queue can be shared between threads and waited on.
+pub struct PriorityQueue<P, T, F: Flavour = Local, C = OrdComparator> {
+    queue: F::Queue<P, T, C>,
+}
+
+pub mod flavour {
+    //! The markers that decide whether a [PriorityQueue](super::PriorityQueue) is thread-safe.
+
+    use super::sync_queue::SyncQueue;
+    use super::SkipList;
+
+    /// Decides what a [PriorityQueue](super::PriorityQueue) keeps its items in.
+    pub trait Flavour: sealed::Sealed {
+        /// What the queue keeps its items in.
+        type Queue<P, T, C>;
+    }
+
+    /// A single-threaded queue, built on a [SkipList].
+    pub struct Local;
+
+    /// A thread-safe queue, built on a [SyncSkipList](crate::SyncSkipList). Only these queues can
+    /// be waited on.
+    pub struct Sync;
+
+    impl Flavour for Local {
+        type Queue<P, T, C> = SkipList<P, T, C>;
+    }
+
+    impl Flavour for Sync {
+        type Queue<P, T, C> = SyncQueue<P, T, C>;
+    }
+
+    mod sealed {
+        pub trait Sealed {}
+
+        impl Sealed for super::Local {}
+
+        impl Sealed for super::Sync {}
+    }
+}
+
+use flavour::{Flavour, Local};
+
+mod sync_queue {
+    use super::*;
+
+    /// The thread-safe queue, which is the only one that can be waited on.
+    ///
+    /// Its items are keyed by their priority and a sequence number, so that each key is unique
+    /// and items of equal priority are kept in the order they were pushed.
+    pub struct SyncQueue<P, T, C> {
+        pub(super) list: SyncSkipList<Sequenced<P>, T, SequenceComparator<C>>,
+        /// The sequence number of the next push.
+        pub(super) seq: AtomicU64,
+        pub(super) waiters: Waiters,
+    }
+
+    impl<P, T, C> SyncQueue<P, T, C> {
+        pub(super) fn with_comparator(comparator: C) -> Self {
+            SyncQueue {
+                list: SyncSkipList::with_comparator(SequenceComparator(comparator)),
+                seq: AtomicU64::new(0),
+                waiters: Waiters::default(),
+            }
+        }
+    }
+
+    /// The key of an item of the thread-safe queue, together with the sequence number of the
+    /// push that added it.
+    pub struct Sequenced<P> {
+        pub(super) priority: P,
+        pub(super) seq: u64,
+    }
+
+    /// Orders items by their priorities according to `C`, and items of equal priority by their
+    /// sequence numbers.
+    pub(super) struct SequenceComparator<C>(C);
+
+    impl<P, C> Comparator<Sequenced<P>> for SequenceComparator<C>
+    where
+        C: Comparator<P>,
+    {
+        fn compare(&self, a: &Sequenced<P>, b: &Sequenced<P>) -> CmpOrdering {
+            self.0
+                .compare(&a.priority, &b.priority)
+                .then_with(|| a.seq.cmp(&b.seq))
+        }
+    }
+}
+
+/// Threads blocked in [pop_blocking](PriorityQueue::pop_blocking) or
+/// [pop_timeout](PriorityQueue::pop_timeout), and tasks waiting on a [PopFuture].
+///
+/// The lock is only taken once a thread or task is about to wait, so pushing and popping stay
+/// lock-free for as long as nobody waits.
+#[derive(Default)]
+struct Waiters {
+    /// The number of waiting threads and registered tasks.
+    count: AtomicUsize,
+    closed: AtomicBool,
+    lock: Mutex<Tasks>,
+    condvar: Condvar,
+}
+
+/// The wakers of waiting tasks, each under the key of the [PopFuture] it belongs to.
+#[derive(Default)]
+struct Tasks {
+    next_key: usize,
+    wakers: VecDeque<(usize, Waker)>,
+}
+
+impl Waiters {
+    /// Wakes a waiting thread and a waiting task, if there are any.
+    fn notify_one(&self) {
+        let mut tasks = self.lock.lock().unwrap();
+        self.condvar.notify_one();
+
+        let waker = tasks.wakers.pop_front().map(|(_, waker)| waker);
+        drop(tasks);
+
+        if let Some(waker) = waker {
+            self.count.fetch_sub(1, Ordering::Relaxed);
+            waker.wake();
+        }
+    }
+
+    fn notify_all(&self) {
+        let mut tasks = self.lock.lock().unwrap();
+        self.condvar.notify_all();
+
+        let wakers = core::mem::take(&mut tasks.wakers);
+        drop(tasks);
+
+        self.count.fetch_sub(wakers.len(), Ordering::Relaxed);
+        wakers.into_iter().for_each(|(_, waker)| waker.wake());
+    }
 }
 
-impl<'domain> PriorityQueue<()> {
-    pub fn new<V: Sync>() -> PriorityQueue<SkipList<'domain, V, ()>> {
+/// Returned by [pop_blocking](PriorityQueue::pop_blocking) once the queue is empty and has been
+/// [closed](PriorityQueue::close).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PopError;
+
+impl fmt::Display for PopError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        "popping from an empty and closed queue".fmt(f)
+    }
+}
+
+impl std::error::Error for PopError {}
+
+/// Returned by [pop_timeout](PriorityQueue::pop_timeout) if no item could be popped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PopTimeoutError {
+    /// Nothing was pushed before the timeout elapsed.
+    Timeout,
+    /// The queue is empty and has been [closed](PriorityQueue::close).
+    Closed,
+}
+
+impl fmt::Display for PopTimeoutError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PopTimeoutError::Timeout => "timed out popping from an empty queue".fmt(f),
+            PopTimeoutError::Closed => "popping from an empty and closed queue".fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for PopTimeoutError {}
+
+/// Only names the constructors, as in `PriorityQueue::new()`.
+impl PriorityQueue<(), ()> {
+    pub fn new<P: Sync, T>() -> PriorityQueue<P, T> {
         PriorityQueue {
             queue: SkipList::new(),
         }
     }
-    pub fn new_sync<V: Sync>() -> PriorityQueue<SyncSkipList<'domain, V, ()>> {
+    pub fn new_sync<P: Sync, T: Sync>() -> PriorityQueue<P, T, flavour::Sync> {
         PriorityQueue {
-            queue: SyncSkipList::new(),
+            queue: sync_queue::SyncQueue::with_comparator(OrdComparator),
+        }
+    }
+
+    /// Creates a queue that pops the highest priority first.
+    pub fn new_max<P: Sync, T>() -> PriorityQueue<P, T, Local, ReverseComparator> {
+        PriorityQueue {
+            queue: SkipList::with_comparator(ReverseComparator(OrdComparator)),
+        }
+    }
+
+    /// Creates a thread-safe queue that pops the highest priority first.
+    pub fn new_sync_max<P: Sync, T: Sync>() -> PriorityQueue<P, T, flavour::Sync, ReverseComparator>
+    {
+        PriorityQueue {
+            queue: sync_queue::SyncQueue::with_comparator(ReverseComparator(OrdComparator)),
         }
     }
 }
 
-unsafe impl<L> Send for PriorityQueue<L> where L: Send + Sync {}
+unsafe impl<P, T, F, C> Send for PriorityQueue<P, T, F, C>
+where
+    F: Flavour,
+    F::Queue<P, T, C>: Send + Sync,
+{
+}
 
-unsafe impl<L> Sync for PriorityQueue<L> where L: Send + Sync {}
+unsafe impl<P, T, F, C> Sync for PriorityQueue<P, T, F, C>
+where
+    F: Flavour,
+    F::Queue<P, T, C>: Send + Sync,
+{
+}
 
-impl<'a, V> PriorityQueue<SkipList<'a, V, ()>>
+impl<P, T, C> PriorityQueue<P, T, Local, C>
 where
-    V: Ord,
+    C: Comparator<P>,
 {
-    pub fn push(&mut self, value: V) {
-        self.queue.insert(value, ());
+    pub fn push(&mut self, priority: P, item: T) {
+        self.queue.insert_multi(priority, item);
+    }
+
+    pub fn peek(&self) -> Option<(&P, &T)> {
+        let first = self.queue.get_first()?;
+
+        Some((first.key(), first.val()))
+    }
+
+    pub fn pop(&mut self) -> Option<(P, T)> {
+        self.queue.remove_first()
     }
 
-    pub fn peek(&'a self) -> Option<&V> {
-        self.queue.get_first()?.key().into()
+    /// Returns the item at the back of the queue, see [pop_max](PriorityQueue::pop_max).
+    pub fn peek_max(&self) -> Option<(&P, &T)> {
+        let last = self.queue.get_last()?;
+
+        Some((last.key(), last.val()))
     }
 
-    pub fn pop(&mut self) -> Option<V> {
-        self.queue.remove_first().map(|(v, ..)| v)
+    /// Pops the item at the back of the queue in O(log n): the highest priority, or the lowest on
+    /// a queue made by [new_max](PriorityQueue::new_max). Of equal priorities, the item pushed
+    /// last is popped first.
+    pub fn pop_max(&mut self) -> Option<(P, T)> {
+        self.queue.remove_last()
     }
 
     pub fn len(&self) -> usize {
@@ -51,76 +281,424 @@ where
     }
 }
 
-impl<'a, V> PriorityQueue<SyncSkipList<'a, V, ()>>
+impl<P, T, C> PriorityQueue<P, T, flavour::Sync, C>
 where
-    V: Ord + Send + Sync + 'a,
+    P: Send + Sync,
+    T: Send + Sync,
+    C: Comparator<P>,
 {
-    pub fn push(&self, value: V) {
-        self.queue.insert(value, ());
+    pub fn push(&self, priority: P, item: T) {
+        let seq = self.queue.seq.fetch_add(1, Ordering::Relaxed);
+        self.queue
+            .list
+            .insert(sync_queue::Sequenced { priority, seq }, item);
+
+        // Pairs with the fences of the waiters: either we see the waiter, or it sees our item.
+        fence(Ordering::SeqCst);
+
+        if self.queue.waiters.count.load(Ordering::Relaxed) > 0 {
+            self.queue.waiters.notify_one();
+        }
     }
 
-    pub fn peek(&'a self) -> Option<sync::Entry<'a, V, ()>> {
-        self.queue.get_first()
+    pub fn peek(&self) -> Option<Entry<'_, P, T>> {
+        self.queue.list.get_first().map(Entry::from)
     }
 
-    pub fn pop(&'a self) -> Option<sync::Entry<'a, V, ()>> {
-        let first = self.queue.get_first()?;
+    /// Pops the first item. It is freed once the returned [Entry] and any other entry of it, for
+    /// example one returned by [peek](PriorityQueue::peek), have been dropped.
+    pub fn pop(&self) -> Option<Entry<'_, P, T>> {
+        self.queue.list.remove_first().map(Entry::from)
+    }
+
+    /// Pops the first item and returns a copy of its priority and the item itself.
+    pub fn pop_cloned(&self) -> Option<(P, T)>
+    where
+        P: Clone,
+        T: Clone,
+    {
+        self.pop().map(|e| (e.priority().clone(), e.item().clone()))
+    }
+
+    /// Pops the first item, parking the thread until one is pushed if the queue is empty. Fails
+    /// once the queue is empty and [closed](PriorityQueue::close).
+    ///
+    /// Only thread-safe queues can be waited on, a [Local] one has nothing to wait for:
+    ///
+    /// ```compile_fail
+    /// let mut queue = skippy_rs::PriorityQueue::new();
+    /// queue.push(1, ());
+    /// let _ = queue.pop_blocking();
+    /// ```
+    pub fn pop_blocking(&self) -> Result<Entry<'_, P, T>, PopError> {
+        self.pop_until(None).map_err(|_| PopError)
+    }
 
-        first.remove()
+    /// Like [pop_blocking](PriorityQueue::pop_blocking), but gives up once `timeout` has elapsed.
+    pub fn pop_timeout(&self, timeout: Duration) -> Result<Entry<'_, P, T>, PopTimeoutError> {
+        self.pop_until(Some(Instant::now() + timeout))
+    }
+
+    /// Returns a [Future] of the first item, which waits for one to be pushed if the queue is
+    /// empty. It works with any executor and fails once the queue is empty and
+    /// [closed](PriorityQueue::close).
+    pub fn pop_async(&self) -> PopFuture<'_, P, T, C> {
+        PopFuture {
+            queue: self,
+            key: None,
+        }
+    }
+
+    /// Returns a [PopStream], which yields items as they are pushed until the queue is closed.
+    pub fn stream(&self) -> PopStream<'_, P, T, C> {
+        PopStream {
+            next: self.pop_async(),
+        }
+    }
+
+    /// Closes the queue, waking all threads and tasks waiting to pop. Items that are still queued
+    /// can be popped as before, only once the queue is empty do blocking pops fail instead of
+    /// waiting.
+    pub fn close(&self) {
+        self.queue.waiters.closed.store(true, Ordering::SeqCst);
+        self.queue.waiters.notify_all();
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.queue.waiters.closed.load(Ordering::SeqCst)
+    }
+
+    fn pop_until(&self, deadline: Option<Instant>) -> Result<Entry<'_, P, T>, PopTimeoutError> {
+        loop {
+            if let Some(item) = self.pop() {
+                return Ok(item);
+            }
+
+            if self.is_closed() {
+                return Err(PopTimeoutError::Closed);
+            }
+
+            if !self.wait(deadline) {
+                return match self.pop() {
+                    Some(item) => Ok(item),
+                    // The queue may have been closed while the wait was expiring.
+                    None if self.is_closed() => Err(PopTimeoutError::Closed),
+                    None => Err(PopTimeoutError::Timeout),
+                };
+            }
+        }
+    }
+
+    /// Parks the thread until the queue might have an item or has been closed. Returns `false`
+    /// if `deadline` passed first.
+    fn wait(&self, deadline: Option<Instant>) -> bool {
+        let guard = self.queue.waiters.lock.lock().unwrap();
+        self.queue.waiters.count.fetch_add(1, Ordering::Relaxed);
+
+        // Pairs with the fence in `push`: either we see the item, or the pusher sees us. In the
+        // latter case, it cannot notify us before we wait, as we hold the lock until then.
+        fence(Ordering::SeqCst);
+
+        let woken = if !self.is_empty() || self.is_closed() {
+            true
+        } else if let Some(deadline) = deadline {
+            match deadline.checked_duration_since(Instant::now()) {
+                Some(timeout) => {
+                    let (_guard, result) = self
+                        .queue
+                        .waiters
+                        .condvar
+                        .wait_timeout(guard, timeout)
+                        .unwrap();
+                    !result.timed_out()
+                }
+                None => false,
+            }
+        } else {
+            let _guard = self.queue.waiters.condvar.wait(guard).unwrap();
+            true
+        };
+
+        self.queue.waiters.count.fetch_sub(1, Ordering::Relaxed);
+        woken
+    }
+
+    /// Returns the item at the back of the queue, see [pop_max](PriorityQueue::pop_max).
+    pub fn peek_max(&self) -> Option<Entry<'_, P, T>> {
+        self.queue.list.get_last().map(Entry::from)
+    }
+
+    /// Pops the item at the back of the queue, like [pop](PriorityQueue::pop) does for the
+    /// front.
+    pub fn pop_max(&self) -> Option<Entry<'_, P, T>> {
+        self.queue.list.remove_last().map(Entry::from)
+    }
+
+    /// Pops the item at the back of the queue, like [pop_cloned](PriorityQueue::pop_cloned)
+    /// does for the front.
+    pub fn pop_max_cloned(&self) -> Option<(P, T)>
+    where
+        P: Clone,
+        T: Clone,
+    {
+        self.pop_max()
+            .map(|e| (e.priority().clone(), e.item().clone()))
     }
 
     pub fn len(&self) -> usize {
-        self.queue.len()
+        self.queue.list.len()
     }
 
     pub fn is_empty(&self) -> bool {
-        self.queue.is_empty()
+        self.queue.list.is_empty()
+    }
+}
+
+/// An item of a thread-safe [PriorityQueue], which is protected from being freed for as long as
+/// the `Entry` lives.
+pub struct Entry<'a, P, T> {
+    entry: sync::Entry<'a, sync_queue::Sequenced<P>, T>,
+}
+
+impl<'a, P, T> Entry<'a, P, T> {
+    pub fn priority(&self) -> &P {
+        &self.entry.key().priority
+    }
+
+    pub fn item(&self) -> &T {
+        self.entry.val()
+    }
+}
+
+impl<'a, P, T> From<sync::Entry<'a, sync_queue::Sequenced<P>, T>> for Entry<'a, P, T> {
+    fn from(entry: sync::Entry<'a, sync_queue::Sequenced<P>, T>) -> Self {
+        Entry { entry }
     }
 }
 
-mod iter {
+/// The [Future] returned by [pop_async](PriorityQueue::pop_async).
+pub struct PopFuture<'a, P, T, C = OrdComparator> {
+    queue: &'a PriorityQueue<P, T, flavour::Sync, C>,
+    /// The key our waker is registered under, if it is.
+    key: Option<usize>,
+}
+
+impl<'a, P, T, C> PopFuture<'a, P, T, C> {
+    /// Removes our waker from the queue. Returns `false` if it was not registered anymore,
+    /// because a `push` woke us.
+    fn unregister(&mut self) -> bool {
+        let Some(key) = self.key.take() else {
+            return false;
+        };
+
+        let waiters = &self.queue.queue.waiters;
+        let mut tasks = waiters.lock.lock().unwrap();
+
+        match tasks.wakers.iter().position(|(k, _)| *k == key) {
+            Some(index) => {
+                tasks.wakers.remove(index);
+                waiters.count.fetch_sub(1, Ordering::Relaxed);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+impl<'a, P, T, C> PopFuture<'a, P, T, C>
+where
+    P: Send + Sync,
+    T: Send + Sync,
+    C: Comparator<P>,
+{
+    fn poll_pop(&mut self, cx: &mut Context<'_>) -> Poll<Result<Entry<'a, P, T>, PopError>> {
+        let waiters = &self.queue.queue.waiters;
+
+        loop {
+            if let Some(item) = self.queue.pop() {
+                self.unregister();
+                return Poll::Ready(Ok(item));
+            }
+
+            if self.queue.is_closed() {
+                self.unregister();
+                return Poll::Ready(Err(PopError));
+            }
+
+            let mut tasks = waiters.lock.lock().unwrap();
+
+            match self
+                .key
+                .and_then(|key| tasks.wakers.iter_mut().find(|(k, _)| *k == key))
+            {
+                Some((_, waker)) => waker.clone_from(cx.waker()),
+                None => {
+                    let key = tasks.next_key;
+                    tasks.next_key = key.wrapping_add(1);
+                    tasks.wakers.push_back((key, cx.waker().clone()));
+
+                    self.key = Some(key);
+                    waiters.count.fetch_add(1, Ordering::Relaxed);
+                }
+            }
+
+            // Pairs with the fence in `push`: either we see the item, or the pusher sees us.
+            fence(Ordering::SeqCst);
+
+            if self.queue.is_empty() && !self.queue.is_closed() {
+                return Poll::Pending;
+            }
+        }
+    }
+}
+
+impl<'a, P, T, C> Future for PopFuture<'a, P, T, C>
+where
+    P: Send + Sync,
+    T: Send + Sync,
+    C: Comparator<P>,
+{
+    type Output = Result<Entry<'a, P, T>, PopError>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        self.get_mut().poll_pop(cx)
+    }
+}
+
+impl<'a, P, T, C> Drop for PopFuture<'a, P, T, C> {
+    fn drop(&mut self) {
+        let registered = self.key.is_some();
+
+        // If a push woke us for an item we will not pop, we pass the wake-up on.
+        if registered && !self.unregister() && !self.queue.queue.list.is_empty() {
+            self.queue.queue.waiters.notify_one();
+        }
+    }
+}
+
+/// A stream of the items of a thread-safe [PriorityQueue], see
+/// [stream](PriorityQueue::stream). It ends once the queue is empty and closed.
+///
+/// Its [poll_next](PopStream::poll_next) has the signature of `Stream::poll_next` from the
+/// `futures` crate, so it can be wrapped into one.
+pub struct PopStream<'a, P, T, C = OrdComparator> {
+    next: PopFuture<'a, P, T, C>,
+}
+
+impl<'a, P, T, C> PopStream<'a, P, T, C>
+where
+    P: Send + Sync,
+    T: Send + Sync,
+    C: Comparator<P>,
+{
+    /// Polls for the next item, returning `Ready(None)` once the queue is closed and drained.
+    pub fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Entry<'a, P, T>>> {
+        self.get_mut().next.poll_pop(cx).map(Result::ok)
+    }
+
+    /// Returns a [Future] of the next item, or `None` once the stream has ended.
+    #[allow(clippy::should_implement_trait)]
+    pub fn next(&mut self) -> impl Future<Output = Option<Entry<'a, P, T>>> + use<'_, 'a, P, T, C> {
+        core::future::poll_fn(|cx| Pin::new(&mut *self).poll_next(cx))
+    }
+}
+
+pub mod iter {
     use super::*;
 
-    impl<'a, V: Ord> PriorityQueue<SkipList<'a, V, ()>> {
-        pub fn iter(&'a self) -> skiplist::iter::Iter<'a, V, ()> {
+    impl<P, T, C> PriorityQueue<P, T, Local, C>
+    where
+        C: Comparator<P>,
+    {
+        pub fn iter(&self) -> skiplist::iter::Iter<'_, P, T, C> {
             self.queue.iter()
         }
 
-        pub fn iter_mut(&'a mut self) -> skiplist::iter::IterMut<'a, V, ()> {
+        pub fn iter_mut(&mut self) -> skiplist::iter::IterMut<'_, P, T, C> {
             self.queue.iter_mut()
         }
     }
 
-    impl<'a, V> IntoIterator for PriorityQueue<SkipList<'a, V, ()>>
+    impl<P, T, C> IntoIterator for PriorityQueue<P, T, Local, C>
     where
-        V: Ord,
+        C: Comparator<P>,
     {
-        type Item = <SkipList<'a, V, ()> as IntoIterator>::Item;
-        type IntoIter = <SkipList<'a, V, ()> as IntoIterator>::IntoIter;
+        type Item = <SkipList<P, T, C> as IntoIterator>::Item;
+        type IntoIter = <SkipList<P, T, C> as IntoIterator>::IntoIter;
 
         fn into_iter(self) -> Self::IntoIter {
             self.queue.into_iter()
         }
     }
 
-    impl<'a, V> PriorityQueue<SyncSkipList<'a, V, ()>>
+    impl<P, T, C> PriorityQueue<P, T, flavour::Sync, C>
     where
-        V: Ord + Send + Sync,
+        P: Send + Sync,
+        T: Send + Sync,
+        C: Comparator<P>,
     {
-        pub fn iter(&'a self) -> sync::iter::Iter<'a, V, ()> {
-            self.queue.iter()
+        pub fn iter(&self) -> SyncIter<'_, P, T, C> {
+            SyncIter {
+                iter: self.queue.list.iter(),
+            }
         }
     }
 
-    impl<'a, V> IntoIterator for PriorityQueue<SyncSkipList<'a, V, ()>>
+    pub struct SyncIter<'a, P, T, C = OrdComparator> {
+        iter: sync::iter::Iter<'a, sync_queue::Sequenced<P>, T, sync_queue::SequenceComparator<C>>,
+    }
+
+    impl<'a, P, T, C> Iterator for SyncIter<'a, P, T, C>
     where
-        V: Ord + Send + Sync,
+        P: Send + Sync,
+        T: Send + Sync,
+        C: Comparator<P>,
     {
-        type Item = <SyncSkipList<'a, V, ()> as IntoIterator>::Item;
-        type IntoIter = <SyncSkipList<'a, V, ()> as IntoIterator>::IntoIter;
+        type Item = Entry<'a, P, T>;
+        fn next(&mut self) -> Option<Self::Item> {
+            self.iter.next().map(Entry::from)
+        }
+    }
+
+    impl<'a, P, T, C> DoubleEndedIterator for SyncIter<'a, P, T, C>
+    where
+        P: Send + Sync,
+        T: Send + Sync,
+        C: Comparator<P>,
+    {
+        fn next_back(&mut self) -> Option<Self::Item> {
+            self.iter.next_back().map(Entry::from)
+        }
+    }
+
+    pub struct SyncIntoIter<P, T> {
+        iter: sync::iter::IntoIter<sync_queue::Sequenced<P>, T>,
+    }
+
+    impl<P, T> Iterator for SyncIntoIter<P, T>
+    where
+        P: Send + Sync,
+        T: Send + Sync,
+    {
+        type Item = (P, T);
+        fn next(&mut self) -> Option<Self::Item> {
+            self.iter.next().map(|(key, item)| (key.priority, item))
+        }
+    }
+
+    impl<P, T, C> IntoIterator for PriorityQueue<P, T, flavour::Sync, C>
+    where
+        P: Send + Sync,
+        T: Send + Sync,
+        C: Comparator<P>,
+    {
+        type Item = (P, T);
+        type IntoIter = SyncIntoIter<P, T>;
 
         fn into_iter(self) -> Self::IntoIter {
-            self.queue.into_iter()
+            SyncIntoIter {
+                iter: self.queue.list.into_iter(),
+            }
         }
     }
 }
@@ -139,7 +717,7 @@ mod pq_test {
         for _ in 0..10_000 {
             rng ^= rng << 3;
 
-            queue.push(rng)
+            queue.push(rng, ())
         }
     }
 
@@ -153,7 +731,7 @@ mod pq_test {
             rng ^= rng >> 9;
             rng ^= rng << 3;
 
-            queue.push(rng)
+            queue.push(rng, ())
         }
 
         for _ in 0..10_000 {
@@ -179,7 +757,7 @@ mod pq_test {
                     queue.pop();
                 }
                 _ => {
-                    queue.push(seed);
+                    queue.push(seed, ());
                 }
             }
         }
@@ -202,11 +780,14 @@ mod pq_test {
 
             match seed % 5 {
                 0 => {
-                    assert_eq!(sq.pop().map(|r: Reverse<u8>| r.0), queue.pop());
+                    assert_eq!(
+                        sq.pop().map(|r: Reverse<u8>| r.0),
+                        queue.pop().map(|(p, _)| p)
+                    );
                 }
                 _ => {
                     sq.push(Reverse(seed));
-                    queue.push(seed);
+                    queue.push(seed, ());
                 }
             }
         }
@@ -223,9 +804,502 @@ mod pq_test {
             seed ^= seed >> 17;
             seed ^= seed << 7;
 
-            queue.push(seed);
+            queue.push(seed, ());
         }
 
         assert!(queue.len() > 0);
     }
+
+    #[test]
+    fn test_payload() {
+        let mut queue = PriorityQueue::new();
+
+        // Neither the closures nor anything else about the jobs can be ordered.
+        queue.push(2, Box::new(|| "second") as Box<dyn Fn() -> &'static str>);
+        queue.push(1, Box::new(|| "first"));
+
+        assert_eq!(queue.peek().map(|(p, job)| (*p, job())), Some((1, "first")));
+        assert_eq!(queue.pop().map(|(p, job)| (p, job())), Some((1, "first")));
+        assert_eq!(queue.pop().map(|(p, job)| (p, job())), Some((2, "second")));
+        assert!(queue.pop().is_none());
+    }
+
+    #[test]
+    fn test_fifo_duplicates() {
+        let mut queue = PriorityQueue::new();
+
+        for id in 0..100 {
+            queue.push(id % 3, id);
+        }
+
+        assert_eq!(queue.len(), 100);
+
+        let popped = core::iter::from_fn(|| queue.pop()).collect::<Vec<_>>();
+        let mut expected = (0..100).map(|id| (id % 3, id)).collect::<Vec<_>>();
+        expected.sort_by_key(|(p, _)| *p);
+
+        assert_eq!(popped, expected);
+    }
+
+    #[test]
+    fn test_sync_fifo_duplicates() {
+        let queue = PriorityQueue::new_sync();
+
+        for id in 0..100 {
+            queue.push(id % 3, id.to_string());
+        }
+
+        assert_eq!(queue.len(), 100);
+
+        let popped = core::iter::from_fn(|| queue.pop_cloned()).collect::<Vec<_>>();
+        let mut expected = (0..100)
+            .map(|id| (id % 3, id.to_string()))
+            .collect::<Vec<_>>();
+        expected.sort_by_key(|(p, _)| *p);
+
+        assert_eq!(popped, expected);
+        assert!(queue.is_empty());
+    }
+
+    #[test]
+    fn test_sync_iter_duplicates() {
+        let queue = PriorityQueue::new_sync();
+
+        for id in 0..10 {
+            queue.push(id % 2, id);
+        }
+
+        let items = queue.iter().map(|e| *e.item()).collect::<Vec<_>>();
+        assert_eq!(items, vec![0, 2, 4, 6, 8, 1, 3, 5, 7, 9]);
+
+        let items = queue.iter().rev().map(|e| *e.item()).collect::<Vec<_>>();
+        assert_eq!(items, vec![9, 7, 5, 3, 1, 8, 6, 4, 2, 0]);
+
+        assert_eq!(queue.pop_max_cloned(), Some((1, 9)));
+
+        let items = queue.into_iter().collect::<Vec<_>>();
+        assert_eq!(
+            items,
+            vec![
+                (0, 0),
+                (0, 2),
+                (0, 4),
+                (0, 6),
+                (0, 8),
+                (1, 1),
+                (1, 3),
+                (1, 5),
+                (1, 7)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_sync_pop() {
+        let queue = PriorityQueue::new_sync();
+
+        for i in (0..1_000).rev() {
+            queue.push(i, i * 2);
+        }
+
+        for i in 0..500 {
+            assert_eq!(queue.pop_cloned(), Some((i, i * 2)));
+        }
+
+        assert_eq!(queue.len(), 500);
+        assert_eq!(queue.iter().count(), 500);
+
+        while queue.pop().is_some() {}
+
+        assert!(queue.is_empty());
+        assert!(queue.peek().is_none());
+    }
+
+    #[test]
+    fn test_sync_concurrent_pop() {
+        use std::sync::Arc;
+        let queue = Arc::new(PriorityQueue::new_sync());
+
+        for i in 0..10_000 {
+            queue.push(i, ());
+        }
+
+        let threads = (0..10)
+            .map(|_| {
+                let queue = queue.clone();
+                std::thread::spawn(move || {
+                    for _ in 0..1_000 {
+                        assert!(queue.pop().is_some());
+                    }
+                })
+            })
+            .collect::<Vec<_>>();
+
+        for thread in threads {
+            thread.join().unwrap()
+        }
+
+        assert!(queue.is_empty());
+        assert!(queue.pop().is_none());
+    }
+
+    #[test]
+    fn test_max() {
+        let mut queue = PriorityQueue::new_max();
+
+        for i in [3, 1, 4, 1, 5, 9, 2, 6] {
+            queue.push(i, ());
+        }
+
+        assert_eq!(queue.peek(), Some((&9, &())));
+        assert_eq!(queue.peek_max(), Some((&1, &())));
+
+        let popped = core::iter::from_fn(|| queue.pop().map(|(p, _)| p)).collect::<Vec<_>>();
+        assert_eq!(popped, vec![9, 6, 5, 4, 3, 2, 1, 1]);
+    }
+
+    #[test]
+    fn test_pop_both_ends() {
+        let mut queue = PriorityQueue::new();
+
+        for i in 0..100 {
+            queue.push(i % 10, i);
+        }
+
+        assert_eq!(queue.peek_max(), Some((&9, &99)));
+        assert_eq!(queue.pop_max(), Some((9, 99)));
+        assert_eq!(queue.pop_max(), Some((9, 89)));
+        assert_eq!(queue.pop(), Some((0, 0)));
+        assert_eq!(queue.len(), 97);
+
+        // Keeping the best 10 items by evicting the worst one.
+        let mut best = PriorityQueue::new_max();
+        for i in 0..1_000u32 {
+            best.push(i.wrapping_mul(7919) % 1_000, i);
+
+            if best.len() > 10 {
+                best.pop_max();
+            }
+        }
+
+        let kept = core::iter::from_fn(|| best.pop().map(|(p, _)| p)).collect::<Vec<_>>();
+        assert_eq!(kept, (990..1_000).rev().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_sync_max() {
+        let queue = PriorityQueue::new_sync_max();
+
+        for i in 0..100 {
+            queue.push(i, i.to_string());
+        }
+
+        assert_eq!(queue.peek().map(|e| *e.priority()), Some(99));
+        assert_eq!(queue.peek_max().map(|e| *e.priority()), Some(0));
+        assert_eq!(queue.pop_cloned(), Some((99, "99".to_string())));
+        assert_eq!(queue.pop_max_cloned(), Some((0, "0".to_string())));
+        assert_eq!(queue.len(), 98);
+    }
+
+    #[test]
+    fn test_sync_concurrent_pop_both_ends() {
+        use std::sync::Arc;
+        let queue = Arc::new(PriorityQueue::new_sync());
+
+        for i in 0..10_000 {
+            queue.push(i, ());
+        }
+
+        let threads = (0..10)
+            .map(|t| {
+                let queue = queue.clone();
+                std::thread::spawn(move || {
+                    (0..1_000)
+                        .map(|_| {
+                            let popped = if t % 2 == 0 {
+                                queue.pop()
+                            } else {
+                                queue.pop_max()
+                            };
+                            *popped.unwrap().priority()
+                        })
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect::<Vec<_>>();
+
+        let mut popped = threads
+            .into_iter()
+            .flat_map(|thread| thread.join().unwrap())
+            .collect::<Vec<_>>();
+        popped.sort();
+
+        assert_eq!(popped, (0..10_000).collect::<Vec<_>>());
+        assert!(queue.is_empty());
+    }
+
+    #[test]
+    fn test_pop_timeout() {
+        use std::time::{Duration, Instant};
+        let queue = PriorityQueue::new_sync();
+
+        let start = Instant::now();
+        assert_eq!(
+            queue.pop_timeout(Duration::from_millis(50)).err(),
+            Some(PopTimeoutError::Timeout)
+        );
+        assert!(start.elapsed() >= Duration::from_millis(50));
+
+        queue.push(1, "a");
+        let popped = queue.pop_timeout(Duration::from_millis(50)).unwrap();
+        assert_eq!((*popped.priority(), *popped.item()), (1, "a"));
+    }
+
+    #[test]
+    fn test_pop_blocking() {
+        use std::sync::Arc;
+        let queue = Arc::new(PriorityQueue::new_sync());
+
+        let consumer = {
+            let queue = queue.clone();
+            std::thread::spawn(move || queue.pop_blocking().map(|e| (*e.priority(), *e.item())))
+        };
+
+        std::thread::sleep(std::time::Duration::from_millis(20));
+        queue.push(7, "seven");
+
+        assert_eq!(consumer.join().unwrap(), Ok((7, "seven")));
+    }
+
+    #[test]
+    fn test_blocked_pop_woken() {
+        use std::sync::Arc;
+        let queue = Arc::new(PriorityQueue::new_sync());
+
+        let blocked = |queue: &Arc<PriorityQueue<u32, &'static str, flavour::Sync>>| {
+            let consumer = {
+                let queue = queue.clone();
+                std::thread::spawn(move || queue.pop_blocking().map(|e| (*e.priority(), *e.item())))
+            };
+
+            // Only wake the consumer once it is parked.
+            while queue.queue.waiters.count.load(Ordering::Relaxed) == 0 {
+                std::thread::yield_now();
+            }
+
+            consumer
+        };
+
+        let consumer = blocked(&queue);
+        queue.push(7, "seven");
+        assert_eq!(consumer.join().unwrap(), Ok((7, "seven")));
+
+        let consumer = blocked(&queue);
+        queue.close();
+        assert_eq!(consumer.join().unwrap(), Err(PopError));
+        assert_eq!(queue.queue.waiters.count.load(Ordering::Relaxed), 0);
+    }
+
+    #[test]
+    fn test_close() {
+        use std::sync::Arc;
+        let queue = Arc::new(PriorityQueue::new_sync());
+
+        let waiters = (0..4)
+            .map(|_| {
+                let queue = queue.clone();
+                std::thread::spawn(move || queue.pop_blocking().map(|e| (*e.priority(), *e.item())))
+            })
+            .collect::<Vec<_>>();
+
+        std::thread::sleep(std::time::Duration::from_millis(20));
+        queue.close();
+
+        for waiter in waiters {
+            assert_eq!(waiter.join().unwrap(), Err::<(u32, ()), _>(PopError));
+        }
+
+        // Items left in a closed queue can still be popped.
+        queue.push(1, ());
+        assert_eq!(queue.pop_blocking().map(|e| *e.priority()), Ok(1));
+        assert_eq!(
+            queue.pop_timeout(std::time::Duration::from_secs(10)).err(),
+            Some(PopTimeoutError::Closed)
+        );
+    }
+
+    #[test]
+    fn test_sync_producers_consumers() {
+        use std::sync::Arc;
+        let queue = Arc::new(PriorityQueue::new_sync());
+
+        let consumers = (0..4)
+            .map(|_| {
+                let queue = queue.clone();
+                std::thread::spawn(move || {
+                    core::iter::from_fn(|| queue.pop_blocking().ok())
+                        .map(|e| *e.priority())
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect::<Vec<_>>();
+
+        let producers = (0..4)
+            .map(|t| {
+                let queue = queue.clone();
+                std::thread::spawn(move || {
+                    for i in 0..1_000 {
+                        queue.push(i * 4 + t, ());
+                    }
+                })
+            })
+            .collect::<Vec<_>>();
+
+        for producer in producers {
+            producer.join().unwrap();
+        }
+
+        queue.close();
+
+        let mut popped = consumers
+            .into_iter()
+            .flat_map(|consumer| consumer.join().unwrap())
+            .collect::<Vec<_>>();
+        popped.sort();
+
+        assert_eq!(popped, (0..4_000).collect::<Vec<_>>());
+    }
+
+    /// Runs `future` to completion on the current thread.
+    fn block_on<F: Future>(future: F) -> F::Output {
+        struct Unpark(std::thread::Thread);
+
+        impl std::task::Wake for Unpark {
+            fn wake(self: std::sync::Arc<Self>) {
+                self.0.unpark()
+            }
+        }
+
+        let waker = Waker::from(std::sync::Arc::new(Unpark(std::thread::current())));
+        let mut cx = Context::from_waker(&waker);
+        let mut future = std::pin::pin!(future);
+
+        loop {
+            match future.as_mut().poll(&mut cx) {
+                Poll::Ready(output) => return output,
+                Poll::Pending => std::thread::park(),
+            }
+        }
+    }
+
+    #[test]
+    fn test_pop_async() {
+        use std::sync::Arc;
+        let queue = Arc::new(PriorityQueue::new_sync());
+
+        queue.push(1, "a");
+        let popped = block_on(queue.pop_async()).unwrap();
+        assert_eq!((*popped.priority(), *popped.item()), (1, "a"));
+
+        let consumer = {
+            let queue = queue.clone();
+            std::thread::spawn(move || {
+                block_on(queue.pop_async()).map(|e| (*e.priority(), *e.item()))
+            })
+        };
+
+        std::thread::sleep(std::time::Duration::from_millis(20));
+        queue.push(2, "b");
+
+        assert_eq!(consumer.join().unwrap(), Ok((2, "b")));
+
+        let consumer = {
+            let queue = queue.clone();
+            std::thread::spawn(move || {
+                block_on(queue.pop_async()).map(|e| (*e.priority(), *e.item()))
+            })
+        };
+
+        std::thread::sleep(std::time::Duration::from_millis(20));
+        queue.close();
+
+        assert_eq!(consumer.join().unwrap(), Err(PopError));
+    }
+
+    #[test]
+    fn test_pop_future_drop() {
+        use std::sync::atomic::AtomicBool;
+        use std::sync::Arc;
+
+        struct Flag(AtomicBool);
+
+        impl std::task::Wake for Flag {
+            fn wake(self: Arc<Self>) {
+                self.0.store(true, Ordering::SeqCst)
+            }
+        }
+
+        let queue = PriorityQueue::new_sync();
+        let flags = [(); 2].map(|_| Arc::new(Flag(AtomicBool::new(false))));
+        let wakers = flags.clone().map(Waker::from);
+
+        let mut futures = [queue.pop_async(), queue.pop_async()];
+        for (future, waker) in futures.iter_mut().zip(&wakers) {
+            let poll = Pin::new(future).poll(&mut Context::from_waker(waker));
+            assert!(poll.is_pending());
+        }
+
+        assert_eq!(queue.queue.waiters.count.load(Ordering::SeqCst), 2);
+
+        queue.push(1, ());
+        assert!(flags[0].0.load(Ordering::SeqCst));
+        assert!(!flags[1].0.load(Ordering::SeqCst));
+
+        // The first future gives up on the item, so the second one gets woken for it.
+        let [first, mut second] = futures;
+        drop(first);
+        assert!(flags[1].0.load(Ordering::SeqCst));
+
+        let poll = Pin::new(&mut second).poll(&mut Context::from_waker(&wakers[1]));
+        assert!(matches!(poll, Poll::Ready(Ok(e)) if *e.priority() == 1));
+        assert_eq!(queue.queue.waiters.count.load(Ordering::SeqCst), 0);
+    }
+
+    #[test]
+    fn test_stream() {
+        use std::sync::Arc;
+        let queue = Arc::new(PriorityQueue::new_sync());
+
+        let consumers = (0..4)
+            .map(|_| {
+                let queue = queue.clone();
+                std::thread::spawn(move || {
+                    block_on(async {
+                        let mut stream = queue.stream();
+                        let mut popped = Vec::new();
+
+                        while let Some(e) = stream.next().await {
+                            popped.push(*e.priority());
+                        }
+
+                        popped
+                    })
+                })
+            })
+            .collect::<Vec<_>>();
+
+        for i in 0..1_000 {
+            queue.push(i, ());
+        }
+
+        queue.close();
+
+        let mut popped = consumers
+            .into_iter()
+            .flat_map(|consumer| consumer.join().unwrap())
+            .collect::<Vec<_>>();
+        popped.sort();
+
+        assert_eq!(popped, (0..1_000).collect::<Vec<_>>());
+    }
 }
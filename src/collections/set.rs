@@ -0,0 +1,559 @@
+use core::borrow::Borrow;
+use core::ops::RangeBounds;
+
+use crate::comparator::{Comparator, OrdComparator};
+use crate::internal::skiplist::SkipList;
+use crate::internal::sync;
+use crate::internal::sync::SkipList as SyncSkipList;
+
+/// An ordered set built on a [SkipList](crate::SkipList). The non-thread safe variation is
+/// [SkipSet](SkipSet), the thread-safe one [SyncSkipSet](SyncSkipSet).
+pub struct SkipSet<K, C = OrdComparator> {
+    list: SkipList<K, (), C>,
+}
+
+impl<K> SkipSet<K> {
+    pub fn new() -> Self {
+        SkipSet {
+            list: SkipList::new(),
+        }
+    }
+}
+
+impl<K> Default for SkipSet<K> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K, C> SkipSet<K, C> {
+    /// Creates an empty set whose keys are ordered by `comparator`.
+    pub fn with_comparator(comparator: C) -> Self {
+        SkipSet {
+            list: SkipList::with_comparator(comparator),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.list.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.list.is_empty()
+    }
+}
+
+impl<K, C> SkipSet<K, C>
+where
+    C: Comparator<K>,
+{
+    /// Adds `key` to the set. Returns `false` if the set already contained it, in which case the
+    /// stored key is kept.
+    pub fn insert(&mut self, key: K) -> bool {
+        self.list.insert_conditionally(key, ()).is_none()
+    }
+
+    pub fn contains<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: ?Sized,
+        C: Comparator<Q>,
+    {
+        self.list.contains_key(key)
+    }
+
+    /// Removes `key` from the set. Returns whether it was present.
+    pub fn remove<Q>(&mut self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: ?Sized,
+        C: Comparator<Q>,
+    {
+        self.take(key).is_some()
+    }
+
+    /// Removes `key` from the set and returns the stored key, if it was present.
+    pub fn take<Q>(&mut self, key: &Q) -> Option<K>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized,
+        C: Comparator<Q>,
+    {
+        self.list.remove(key).map(|(key, ..)| key)
+    }
+
+    pub fn first(&self) -> Option<&K> {
+        self.list.get_first().map(|e| e.key())
+    }
+
+    pub fn last(&self) -> Option<&K> {
+        self.list.get_last().map(|e| e.key())
+    }
+
+    /// Removes and returns the first key of the set.
+    pub fn pop_first(&mut self) -> Option<K> {
+        self.list.remove_first().map(|(key, ..)| key)
+    }
+
+    pub fn iter<'a>(&'a self) -> iter::Iter<'a, K, C> {
+        iter::Iter::from_set(self)
+    }
+
+    /// Returns an iterator over the keys that lie within `range`.
+    pub fn range<'a, Q, R>(&'a self, range: R) -> iter::Range<'a, K, R, Q, C>
+    where
+        K: Borrow<Q>,
+        R: RangeBounds<Q>,
+        Q: ?Sized,
+        C: Comparator<Q>,
+    {
+        iter::Range::from_set(self, range)
+    }
+}
+
+/// The thread-safe variation of [SkipSet](SkipSet), built on a
+/// [SyncSkipList](crate::SyncSkipList).
+pub struct SyncSkipSet<K, C = OrdComparator> {
+    list: SyncSkipList<K, (), C>,
+}
+
+impl<K> SyncSkipSet<K> {
+    pub fn new() -> Self {
+        SyncSkipSet {
+            list: SyncSkipList::new(),
+        }
+    }
+}
+
+impl<K> Default for SyncSkipSet<K> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K, C> SyncSkipSet<K, C> {
+    /// Creates an empty set whose keys are ordered by `comparator`.
+    pub fn with_comparator(comparator: C) -> Self {
+        SyncSkipSet {
+            list: SyncSkipList::with_comparator(comparator),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.list.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.list.is_empty()
+    }
+}
+
+impl<K, C> SyncSkipSet<K, C>
+where
+    K: Send + Sync,
+    C: Comparator<K>,
+{
+    /// Adds `key` to the set. Returns `false` if the set already contained it, in which case the
+    /// stored key is kept.
+    pub fn insert(&self, key: K) -> bool {
+        self.list.try_insert(key, ()).is_ok()
+    }
+
+    pub fn contains<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: ?Sized,
+        C: Comparator<Q>,
+    {
+        self.list.contains_key(key)
+    }
+
+    /// Removes `key` from the set. Returns the removed [Entry](Entry), if the key was present.
+    pub fn remove<'a, Q>(&'a self, key: &Q) -> Option<Entry<'a, K>>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized,
+        C: Comparator<Q>,
+    {
+        self.list.remove(key).map(Entry::from)
+    }
+
+    pub fn first<'a>(&'a self) -> Option<Entry<'a, K>> {
+        self.list.get_first().map(Entry::from)
+    }
+
+    pub fn last<'a>(&'a self) -> Option<Entry<'a, K>> {
+        self.list.get_last().map(Entry::from)
+    }
+
+    pub fn iter<'a>(&'a self) -> iter::SyncIter<'a, K, C> {
+        iter::SyncIter::from_set(self)
+    }
+
+    /// Returns an iterator over the keys that lie within `range`.
+    pub fn range<'a, Q, R>(&'a self, range: R) -> iter::SyncRange<'a, K, R, Q, C>
+    where
+        K: Borrow<Q>,
+        R: RangeBounds<Q>,
+        Q: ?Sized,
+        C: Comparator<Q>,
+    {
+        iter::SyncRange::from_set(self, range)
+    }
+}
+
+/// A key of a [SyncSkipSet](SyncSkipSet), which is protected from being freed for as long as the
+/// `Entry` lives.
+pub struct Entry<'a, K> {
+    entry: sync::Entry<'a, K, ()>,
+}
+
+impl<'a, K> Entry<'a, K> {
+    pub fn key(&self) -> &K {
+        self.entry.key()
+    }
+}
+
+impl<'a, K> core::ops::Deref for Entry<'a, K> {
+    type Target = K;
+
+    fn deref(&self) -> &Self::Target {
+        self.key()
+    }
+}
+
+impl<'a, K> From<sync::Entry<'a, K, ()>> for Entry<'a, K> {
+    fn from(entry: sync::Entry<'a, K, ()>) -> Self {
+        Entry { entry }
+    }
+}
+
+pub mod iter {
+    use super::{Entry, SkipSet, SyncSkipSet};
+    use crate::comparator::{Comparator, OrdComparator};
+    use crate::internal::{skiplist, sync};
+    use core::borrow::Borrow;
+    use core::iter::{DoubleEndedIterator, FromIterator, IntoIterator, Iterator};
+    use core::ops::{RangeBounds, RangeFull};
+
+    pub struct Iter<'a, K, C = OrdComparator> {
+        iter: skiplist::iter::Iter<'a, K, (), C>,
+    }
+
+    impl<'a, K, C> Iter<'a, K, C>
+    where
+        C: Comparator<K>,
+    {
+        pub fn from_set(set: &'a SkipSet<K, C>) -> Self {
+            Iter {
+                iter: set.list.iter(),
+            }
+        }
+    }
+
+    impl<'a, K, C> Iterator for Iter<'a, K, C>
+    where
+        C: Comparator<K>,
+    {
+        type Item = &'a K;
+        fn next(&mut self) -> Option<Self::Item> {
+            self.iter.next().map(|e| e.key())
+        }
+    }
+
+    impl<'a, K, C> DoubleEndedIterator for Iter<'a, K, C>
+    where
+        C: Comparator<K>,
+    {
+        fn next_back(&mut self) -> Option<Self::Item> {
+            self.iter.next_back().map(|e| e.key())
+        }
+    }
+
+    pub struct Range<'a, K, R, Q: ?Sized = K, C = OrdComparator> {
+        range: skiplist::iter::Range<'a, K, (), R, Q, C>,
+    }
+
+    impl<'a, K, R, Q, C> Range<'a, K, R, Q, C>
+    where
+        K: Borrow<Q>,
+        R: RangeBounds<Q>,
+        Q: ?Sized,
+        C: Comparator<K> + Comparator<Q>,
+    {
+        pub fn from_set(set: &'a SkipSet<K, C>, range: R) -> Self {
+            Range {
+                range: set.list.range(range),
+            }
+        }
+    }
+
+    impl<'a, K, R, Q, C> Iterator for Range<'a, K, R, Q, C>
+    where
+        K: Borrow<Q>,
+        R: RangeBounds<Q>,
+        Q: ?Sized,
+        C: Comparator<K> + Comparator<Q>,
+    {
+        type Item = &'a K;
+        fn next(&mut self) -> Option<Self::Item> {
+            self.range.next().map(|e| e.key())
+        }
+    }
+
+    impl<'a, K, R, Q, C> DoubleEndedIterator for Range<'a, K, R, Q, C>
+    where
+        K: Borrow<Q>,
+        R: RangeBounds<Q>,
+        Q: ?Sized,
+        C: Comparator<K> + Comparator<Q>,
+    {
+        fn next_back(&mut self) -> Option<Self::Item> {
+            self.range.next_back().map(|e| e.key())
+        }
+    }
+
+    pub struct IntoIter<K, C = OrdComparator> {
+        iter: skiplist::iter::IntoIter<K, (), C>,
+    }
+
+    impl<K, C> Iterator for IntoIter<K, C>
+    where
+        C: Comparator<K>,
+    {
+        type Item = K;
+        fn next(&mut self) -> Option<Self::Item> {
+            self.iter.next().map(|(key, ..)| key)
+        }
+    }
+
+    impl<K, C> IntoIterator for SkipSet<K, C>
+    where
+        C: Comparator<K>,
+    {
+        type Item = K;
+        type IntoIter = IntoIter<K, C>;
+
+        fn into_iter(self) -> Self::IntoIter {
+            IntoIter {
+                iter: self.list.into_iter(),
+            }
+        }
+    }
+
+    impl<K, C> FromIterator<K> for SkipSet<K, C>
+    where
+        C: Comparator<K> + Default,
+    {
+        fn from_iter<T: IntoIterator<Item = K>>(iter: T) -> Self {
+            let mut set = Self::with_comparator(C::default());
+            for key in iter {
+                set.insert(key);
+            }
+
+            set
+        }
+    }
+
+    pub struct SyncIter<'a, K, C = OrdComparator> {
+        range: SyncRange<'a, K, RangeFull, K, C>,
+    }
+
+    impl<'a, K, C> SyncIter<'a, K, C>
+    where
+        K: Send + Sync,
+        C: Comparator<K>,
+    {
+        pub fn from_set(set: &'a SyncSkipSet<K, C>) -> Self {
+            SyncIter {
+                range: SyncRange::from_set(set, ..),
+            }
+        }
+    }
+
+    impl<'a, K, C> Iterator for SyncIter<'a, K, C>
+    where
+        K: Send + Sync,
+        C: Comparator<K>,
+    {
+        type Item = Entry<'a, K>;
+        fn next(&mut self) -> Option<Self::Item> {
+            self.range.next()
+        }
+    }
+
+    impl<'a, K, C> DoubleEndedIterator for SyncIter<'a, K, C>
+    where
+        K: Send + Sync,
+        C: Comparator<K>,
+    {
+        fn next_back(&mut self) -> Option<Self::Item> {
+            self.range.next_back()
+        }
+    }
+
+    pub struct SyncRange<'a, K, R, Q: ?Sized = K, C = OrdComparator> {
+        range: sync::iter::Range<'a, K, (), R, Q, C>,
+    }
+
+    impl<'a, K, R, Q, C> SyncRange<'a, K, R, Q, C>
+    where
+        K: Send + Sync,
+        K: Borrow<Q>,
+        R: RangeBounds<Q>,
+        Q: ?Sized,
+        C: Comparator<K> + Comparator<Q>,
+    {
+        pub fn from_set(set: &'a SyncSkipSet<K, C>, range: R) -> Self {
+            SyncRange {
+                range: set.list.range(range),
+            }
+        }
+    }
+
+    impl<'a, K, R, Q, C> Iterator for SyncRange<'a, K, R, Q, C>
+    where
+        K: Send + Sync,
+        K: Borrow<Q>,
+        R: RangeBounds<Q>,
+        Q: ?Sized,
+        C: Comparator<K> + Comparator<Q>,
+    {
+        type Item = Entry<'a, K>;
+        fn next(&mut self) -> Option<Self::Item> {
+            self.range.next().map(Entry::from)
+        }
+    }
+
+    impl<'a, K, R, Q, C> DoubleEndedIterator for SyncRange<'a, K, R, Q, C>
+    where
+        K: Send + Sync,
+        K: Borrow<Q>,
+        R: RangeBounds<Q>,
+        Q: ?Sized,
+        C: Comparator<K> + Comparator<Q>,
+    {
+        fn next_back(&mut self) -> Option<Self::Item> {
+            self.range.next_back().map(Entry::from)
+        }
+    }
+
+    pub struct SyncIntoIter<K> {
+        iter: sync::iter::IntoIter<K, ()>,
+    }
+
+    impl<K> Iterator for SyncIntoIter<K>
+    where
+        K: Send + Sync,
+    {
+        type Item = K;
+        fn next(&mut self) -> Option<Self::Item> {
+            self.iter.next().map(|(key, ..)| key)
+        }
+    }
+
+    impl<K, C> IntoIterator for SyncSkipSet<K, C>
+    where
+        K: Send + Sync,
+        C: Comparator<K>,
+    {
+        type Item = K;
+        type IntoIter = SyncIntoIter<K>;
+
+        fn into_iter(self) -> Self::IntoIter {
+            SyncIntoIter {
+                iter: self.list.into_iter(),
+            }
+        }
+    }
+
+    impl<K, C> FromIterator<K> for SyncSkipSet<K, C>
+    where
+        K: Send + Sync,
+        C: Comparator<K> + Default,
+    {
+        fn from_iter<T: IntoIterator<Item = K>>(iter: T) -> Self {
+            let set = Self::with_comparator(C::default());
+            for key in iter {
+                set.insert(key);
+            }
+
+            set
+        }
+    }
+}
+
+#[cfg(test)]
+mod set_test {
+    use super::*;
+
+    #[test]
+    fn test_set() {
+        let mut set = SkipSet::new();
+
+        for i in [5, 3, 8, 1, 9] {
+            assert!(set.insert(i));
+        }
+
+        assert!(!set.insert(3));
+        assert_eq!(set.len(), 5);
+        assert!(set.contains(&8));
+        assert_eq!(set.first(), Some(&1));
+        assert_eq!(set.last(), Some(&9));
+        assert_eq!(set.iter().copied().collect::<Vec<_>>(), vec![1, 3, 5, 8, 9]);
+        assert_eq!(
+            set.range(2..9).rev().copied().collect::<Vec<_>>(),
+            vec![8, 5, 3]
+        );
+
+        assert!(set.remove(&5));
+        assert!(!set.remove(&5));
+        assert_eq!(set.pop_first(), Some(1));
+        assert_eq!(set.into_iter().collect::<Vec<_>>(), vec![3, 8, 9]);
+    }
+
+    #[test]
+    fn test_sync_set() {
+        let set: SyncSkipSet<_> = (0..10).map(|i| i * 2).collect();
+
+        assert!(!set.insert(4));
+        assert!(set.insert(5));
+        assert!(set.contains(&5));
+        assert_eq!(set.first().map(|e| *e), Some(0));
+        assert_eq!(set.last().map(|e| *e), Some(18));
+        assert_eq!(
+            set.range(3..9).map(|e| *e).collect::<Vec<_>>(),
+            vec![4, 5, 6, 8]
+        );
+        assert_eq!(
+            set.iter().rev().take(2).map(|e| *e).collect::<Vec<_>>(),
+            vec![18, 16]
+        );
+
+        assert_eq!(set.remove(&5).map(|e| *e), Some(5));
+        assert!(set.remove(&5).is_none());
+        assert_eq!(set.len(), 10);
+        assert_eq!(set.into_iter().count(), 10);
+    }
+
+    #[test]
+    fn test_sync_set_insert() {
+        use std::sync::Arc;
+        let set = Arc::new(SyncSkipSet::new());
+
+        let threads = (0..10)
+            .map(|_| {
+                let set = set.clone();
+                std::thread::spawn(move || (0..100).filter(|&i| set.insert(i)).count())
+            })
+            .collect::<Vec<_>>();
+
+        let inserted = threads
+            .into_iter()
+            .map(|thread| thread.join().unwrap())
+            .sum::<usize>();
+
+        assert_eq!(inserted, 100);
+        assert_eq!(set.len(), 100);
+    }
+}
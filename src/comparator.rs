@@ -0,0 +1,46 @@
+use core::cmp::Ordering;
+
+/// Defines the order of the keys in a [SkipList](crate::SkipList).
+///
+/// A `Comparator` is stored in the list it orders, which allows orderings to be picked, and to
+/// depend on context, at runtime. It has to be a total order, just like [Ord].
+pub trait Comparator<K: ?Sized> {
+    fn compare(&self, a: &K, b: &K) -> Ordering;
+}
+
+/// The default [Comparator], which orders keys by their [Ord] implementation.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct OrdComparator;
+
+impl<K> Comparator<K> for OrdComparator
+where
+    K: ?Sized + Ord,
+{
+    fn compare(&self, a: &K, b: &K) -> Ordering {
+        a.cmp(b)
+    }
+}
+
+/// Reverses the order of another [Comparator], which is the [OrdComparator] by default.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct ReverseComparator<C = OrdComparator>(pub C);
+
+impl<K, C> Comparator<K> for ReverseComparator<C>
+where
+    K: ?Sized,
+    C: Comparator<K>,
+{
+    fn compare(&self, a: &K, b: &K) -> Ordering {
+        self.0.compare(b, a)
+    }
+}
+
+impl<K, F> Comparator<K> for F
+where
+    K: ?Sized,
+    F: Fn(&K, &K) -> Ordering,
+{
+    fn compare(&self, a: &K, b: &K) -> Ordering {
+        self(a, b)
+    }
+}
@@ -1,40 +1,51 @@
-use core::{ptr::NonNull, sync::atomic::Ordering};
+use core::{
+    borrow::Borrow,
+    ops::{Bound, RangeBounds},
+    ptr::NonNull,
+    sync::atomic::{AtomicUsize, Ordering},
+};
 use std::marker::PhantomData;
 
-use crate::internal::utils::{skiplist_basics, GeneratesHeight, Levels, Node, HEIGHT};
+use crate::comparator::{Comparator, OrdComparator};
+use crate::internal::utils::{self, skiplist_basics, GeneratesHeight, HEIGHT};
 
-skiplist_basics!(SkipList);
+/// The nodes of this list keep the span of each of their levels.
+type Node<K, V> = utils::Node<K, V, AtomicUsize>;
 
-impl<'domain, K, V> SkipList<'domain, K, V>
+skiplist_basics!(SkipList, AtomicUsize);
+
+impl<K, V, C> SkipList<K, V, C>
 where
-    K: Ord,
+    C: Comparator<K>,
 {
     /// Inserts a value in the list given a key.
     pub fn insert(&mut self, key: K, val: V) -> Option<V> {
-        self.internal_insert(key, val, true)
+        self.internal_insert(key, val, true).map(|(_, val)| val)
     }
 
     pub fn insert_conditionally(&mut self, key: K, val: V) -> Option<V> {
-        self.internal_insert(key, val, false)
+        self.internal_insert(key, val, false).map(|(_, val)| val)
     }
 
-    fn internal_insert(&mut self, key: K, mut val: V, replace: bool) -> Option<V> {
+    /// Should `key` already be present, the value that is not kept is returned together with
+    /// `key`.
+    fn internal_insert(&mut self, key: K, mut val: V, replace: bool) -> Option<(K, V)> {
         // After this check, whether we are holding the head or a regular Node will
         // not impact the operation.
         unsafe {
-            let mut insertion_point = self.find(&key);
+            let mut insertion_point = self.find(&key, false);
 
             if let Some(mut target) = insertion_point.target.take() {
                 if replace {
                     core::mem::swap(&mut target.as_mut().val, &mut val);
                 }
 
-                return Some(val);
+                return Some((key, val));
             }
 
             let new_node = Node::new_rand_height(key, val, self);
 
-            self.link_nodes(new_node, insertion_point.prev);
+            self.link_nodes(new_node, insertion_point.prev, insertion_point.ranks);
 
             self.state.len.fetch_add(1, Ordering::Relaxed);
 
@@ -42,6 +53,161 @@ where
         }
     }
 
+    /// Inserts `val` for `key` without replacing any existing values, which allows the list to be
+    /// used as a multimap. Values of the same key are kept in insertion order.
+    pub fn insert_multi(&mut self, key: K, val: V) {
+        unsafe {
+            // We link behind all nodes of the same key.
+            let insertion_point = self.find_by(|k| self.compare(k, &key).is_le());
+
+            let new_node = Node::new_rand_height(key, val, self);
+
+            self.link_nodes(new_node, insertion_point.prev, insertion_point.ranks);
+
+            self.state.len.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    /// Builds a list from an iterator whose keys are sorted in ascending order and contain no
+    /// duplicates. Rather than searching for each insertion point, the towers are linked from left
+    /// to right in a single pass, which takes O(n).
+    ///
+    /// In debug builds, this panics if the keys are not strictly ascending.
+    pub fn from_sorted_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = (K, V)>,
+        C: Default,
+    {
+        let list = Self::with_comparator(C::default());
+        let head = list.head.as_ptr().cast::<Node<K, V>>();
+
+        // The last node linked on each level, and its position.
+        let mut last = [head; HEIGHT];
+        let mut ranks = [0; HEIGHT];
+        let mut len = 0;
+
+        unsafe {
+            for (key, val) in iter {
+                debug_assert!(
+                    len == 0 || list.compare(&(*last[0]).key, &key).is_lt(),
+                    "keys must be sorted and unique"
+                );
+
+                let new_node = Node::new_rand_height(key, val, &list);
+                len += 1;
+
+                for i in 0..(*new_node).height() {
+                    (&(*last[i]).levels)[i].store_ptr(new_node);
+                    (*last[i]).levels.set_span(i, len - ranks[i]);
+                    (*new_node).add_ref();
+
+                    last[i] = new_node;
+                    ranks[i] = len;
+                }
+            }
+
+            // The spans of the last pointers on each level cover the remainder of the list.
+            for i in 0..HEIGHT {
+                (*last[i]).levels.set_span(i, len - ranks[i]);
+            }
+        }
+
+        list.state.len.store(len, Ordering::Relaxed);
+
+        list
+    }
+
+    /// Splits the list in two at `key`. The entries whose keys are greater than or equal to `key`
+    /// are moved to the returned list.
+    ///
+    /// The towers are cut at every level at the boundary, so this takes O(log n).
+    pub fn split_off<Q>(&mut self, key: &Q) -> Self
+    where
+        K: Borrow<Q>,
+        Q: ?Sized,
+        C: Comparator<Q> + Clone,
+    {
+        let other = Self::with_comparator(self.comparator.clone());
+
+        if self.is_empty() {
+            return other;
+        }
+
+        unsafe {
+            let SearchResult { prev, ranks, .. } = self.find(key, true);
+            let other_head = other.head.as_ref();
+
+            for (i, prev) in prev.iter().enumerate() {
+                // the spans past the boundary now start from the head of `other`
+                let span = ranks[i] + prev.levels.span(i) - ranks[0];
+                other_head.levels[i].store_ptr(prev.levels[i].load_ptr());
+                other_head.levels.set_span(i, span);
+
+                prev.levels[i].store_ptr(core::ptr::null_mut());
+                prev.levels.set_span(i, ranks[0] - ranks[i]);
+            }
+
+            other
+                .state
+                .len
+                .store(self.len() - ranks[0], Ordering::Relaxed);
+            other.state.max_height.store(
+                self.state.max_height.load(Ordering::Relaxed),
+                Ordering::Relaxed,
+            );
+            self.state.len.store(ranks[0], Ordering::Relaxed);
+        }
+
+        other
+    }
+
+    /// Moves all entries of `other` into the list, leaving `other` empty.
+    ///
+    /// If no key of `other` is less than those of the list, the towers of `other` are linked
+    /// behind ours at every level, which takes O(log n). Otherwise, the entries of `other` are
+    /// inserted one by one. Either way, keys present in both lists keep all of their entries,
+    /// with those of `other` following ours.
+    pub fn append(&mut self, other: &mut Self) {
+        if other.is_empty() {
+            return;
+        }
+
+        let ordered = match (self.get_last(), other.get_first()) {
+            (Some(last), Some(first)) => self.compare(last.key(), first.key()).is_le(),
+            _ => true,
+        };
+
+        if !ordered {
+            while let Some((key, val)) = other.remove_first() {
+                self.insert_multi(key, val);
+            }
+
+            return;
+        }
+
+        unsafe {
+            let SearchResult { prev, ranks, .. } = self.find_by(|_| true);
+            let other_head = other.head.as_ref();
+            let len = self.len();
+
+            for (i, prev) in prev.iter().enumerate() {
+                prev.levels[i].store_ptr(other_head.levels[i].load_ptr());
+                prev.levels
+                    .set_span(i, len - ranks[i] + other_head.levels.span(i));
+
+                other_head.levels[i].store_ptr(core::ptr::null_mut());
+                other_head.levels.set_span(i, 0);
+            }
+
+            self.state.len.fetch_add(other.len(), Ordering::Relaxed);
+            self.state.max_height.fetch_max(
+                other.state.max_height.load(Ordering::Relaxed),
+                Ordering::Relaxed,
+            );
+            other.state.len.store(0, Ordering::Relaxed);
+        }
+    }
+
     /// This function is unsafe, as it does not check whether new_node or link node are valid
     /// pointers.
     /// To call this function safely:
@@ -49,22 +215,73 @@ where
     /// - link_node cannot be null
     /// - no pointer tower along the path can have a null pointer pointing backwards
     /// - a tower of sufficient height must eventually be reached, the list head can be this tower
-    unsafe fn link_nodes(&self, new_node: *mut Node<K, V>, prev: [&Levels<K, V>; HEIGHT]) {
-        // iterate over all the levels in the new nodes pointer tower
-        for (i, levels) in prev.iter().enumerate().take((*new_node).height()) {
-            // move backwards until a pointer tower of sufficient hight is reached
+    ///
+    /// `ranks` holds the position of each node in `prev`, with the head at 0.
+    unsafe fn link_nodes(
+        &self,
+        new_node: *mut Node<K, V>,
+        prev: [&Node<K, V>; HEIGHT],
+        ranks: [usize; HEIGHT],
+    ) {
+        let height = (*new_node).height();
+
+        for (i, prev) in prev.iter().enumerate() {
+            // the levels above the new node skip over one more node
+            if i >= height {
+                prev.levels.set_span(i, prev.levels.span(i) + 1);
+                continue;
+            }
+
+            // the new node takes over the part of the span that follows it
+            let before = ranks[0] - ranks[i];
+            (*new_node).levels.set_span(i, prev.levels.span(i) - before);
+            prev.levels.set_span(i, before + 1);
+
             unsafe {
-                (*new_node).levels[i].store_ptr(levels[i].load_ptr());
-                levels[i].store_ptr(new_node);
+                (&(*new_node).levels)[i].store_ptr(prev.levels[i].load_ptr());
+                prev.levels[i].store_ptr(new_node);
                 (*new_node).add_ref();
             }
         }
     }
 
-    pub fn remove(&mut self, key: &K) -> Option<(K, V)> {
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<(K, V)>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized,
+        C: Comparator<Q>,
+    {
+        self.internal_remove(key)
+    }
+
+    /// Removes the oldest value of `key`. See [insert_multi](SkipList::insert_multi).
+    ///
+    /// This is the same as [remove](SkipList::remove).
+    pub fn remove_one<Q>(&mut self, key: &Q) -> Option<(K, V)>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized,
+        C: Comparator<Q>,
+    {
         self.internal_remove(key)
     }
 
+    /// Removes all values of `key` and returns how many were removed.
+    pub fn remove_all<Q>(&mut self, key: &Q) -> usize
+    where
+        K: Borrow<Q>,
+        Q: ?Sized,
+        C: Comparator<Q>,
+    {
+        let mut removed = 0;
+
+        while self.internal_remove(key).is_some() {
+            removed += 1;
+        }
+
+        removed
+    }
+
     pub fn remove_first(&mut self) -> Option<(K, V)> {
         if self.is_empty() {
             return None;
@@ -76,68 +293,135 @@ where
         }
     }
 
-    fn internal_remove(&mut self, key: &K) -> Option<(K, V)> {
+    /// Removes the last entry of the list in O(log n).
+    pub fn remove_last(&mut self) -> Option<(K, V)> {
+        let last = self.len().checked_sub(1)?;
+
+        self.remove_nth(last)
+    }
+
+    fn internal_remove<Q>(&mut self, key: &Q) -> Option<(K, V)>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized,
+        C: Comparator<Q>,
+    {
         if self.is_empty() {
             return None;
         }
 
         unsafe {
-            match self.find(key) {
-                SearchResult {
-                    target: Some(target),
-                    prev,
-                } => {
-                    let target = target.as_ptr();
-                    let key = core::ptr::read(&(*target).key);
-                    let val = core::ptr::read(&(*target).val);
-
-                    self.unlink(target, prev);
-                    Node::<K, V>::dealloc(target);
-                    self.state.len.fetch_sub(1, Ordering::Relaxed);
-
-                    Some((key, val))
-                }
-                _ => None,
+            let search = self.find(key, false);
+            self.remove_found(search)
+        }
+    }
+
+    /// Removes the target of `search` from the list, if there is one.
+    unsafe fn remove_found(&mut self, search: SearchResult<'_, K, V>) -> Option<(K, V)> {
+        match search {
+            SearchResult {
+                target: Some(target),
+                prev,
+                ..
+            } => {
+                let target = target.as_ptr();
+                let key = core::ptr::read(&(*target).key);
+                let val = core::ptr::read(&(*target).val);
+
+                self.unlink(target, prev);
+                Node::<K, V>::dealloc(target);
+                self.state.len.fetch_sub(1, Ordering::Relaxed);
+
+                Some((key, val))
             }
+            _ => None,
+        }
+    }
+
+    /// Removes the entry at `index`, counting from 0, in O(log n).
+    pub fn remove_nth(&mut self, index: usize) -> Option<(K, V)> {
+        if index >= self.len() {
+            return None;
+        }
+
+        unsafe {
+            let search = self.find_nth(index);
+            self.remove_found(search)
         }
     }
 
     /// Logically removes the node from the list by linking its adjacent nodes to one-another.
-    fn unlink(&mut self, node: *mut Node<K, V>, prev: [&Levels<K, V>; HEIGHT]) {
+    fn unlink(&mut self, node: *mut Node<K, V>, prev: [&Node<K, V>; HEIGHT]) {
         // safety check against UB caused by unlinking the head
         if self.is_head(node) {
             panic!()
         }
         unsafe {
-            for (i, levels) in prev.iter().enumerate().take((*node).height()) {
-                levels[i].store_ptr((*node).levels[i].load_ptr());
+            let height = (*node).height();
+
+            for (i, prev) in prev.iter().enumerate() {
+                if i >= height {
+                    prev.levels.set_span(i, prev.levels.span(i) - 1);
+                    continue;
+                }
+
+                let span = prev.levels.span(i) + (*node).levels.span(i) - 1;
+                prev.levels.set_span(i, span);
+                prev.levels[i].store_ptr((&(*node).levels)[i].load_ptr());
             }
         }
     }
 
-    unsafe fn unlink_level(
-        prev: *mut Node<K, V>,
-        curr: *mut Node<K, V>,
-        level: usize,
-    ) -> *mut Node<K, V> {
-        let next = (*curr).levels[level].load_ptr();
+    /// This method is `unsafe` as it may return the head typecast as a Node, which can
+    /// cause UB if not handled appropriately. If the return value is Ok(...) then it is a
+    /// regular Node. If it is Err(...) then it is the head.
+    ///
+    /// If `search_closest` is set, the target is the first node whose key is greater or equal to
+    /// `key`, rather than only an exact match.
+    unsafe fn find<'a, Q>(&self, key: &Q, search_closest: bool) -> SearchResult<'a, K, V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized,
+        C: Comparator<Q>,
+    {
+        let mut search = self.find_by(|k| self.compare(k, key).is_lt());
 
-        if (*curr).sub_ref() == 0 {
-            Node::<K, V>::dealloc(curr);
+        if !search_closest {
+            search.target = search
+                .target
+                .filter(|t| unsafe { self.compare(&t.as_ref().key, key).is_eq() });
         }
 
-        (*prev).levels[level].store_ptr(next);
-        next
+        search
     }
 
-    /// This method is `unsafe` as it may return the head typecast as a Node, which can
-    /// cause UB if not handled appropriately. If the return value is Ok(...) then it is a
-    /// regular Node. If it is Err(...) then it is the head.
-    unsafe fn find<'a>(&self, key: &K) -> SearchResult<'a, K, V> {
+    /// Searches for the first node whose key is not `before` the target, where `before` must hold
+    /// for a prefix of the list. The predecessors on each level are the last nodes for which
+    /// `before` holds.
+    unsafe fn find_by<'a, F>(&self, before: F) -> SearchResult<'a, K, V>
+    where
+        F: Fn(&K) -> bool,
+    {
+        self.search(|node, _| before(&node.key))
+    }
+
+    /// Searches for the node at `index`. See [find_by](SkipList::find_by).
+    unsafe fn find_nth<'a>(&self, index: usize) -> SearchResult<'a, K, V> {
+        self.search(|_, rank| rank <= index)
+    }
+
+    /// The search underlying [find_by](SkipList::find_by), where `before` is also passed the
+    /// position of the node, counting from 1.
+    unsafe fn search<'a, F>(&self, before: F) -> SearchResult<'a, K, V>
+    where
+        F: Fn(&Node<K, V>, usize) -> bool,
+    {
         let mut level = self.state.max_height.load(Ordering::Relaxed);
         let head = unsafe { &(*self.head.as_ptr()) };
 
-        let mut prev = [&head.levels; HEIGHT];
+        let mut prev = [&*self.head.as_ptr().cast::<Node<K, V>>(); HEIGHT];
+        let mut ranks = [0; HEIGHT];
+        let mut rank = 0;
 
         // find the first and highest node tower
         while level > 1 && head.levels[level - 1].load_ptr().is_null() {
@@ -145,45 +429,64 @@ where
         }
 
         let mut curr = self.head.as_ptr().cast::<Node<K, V>>();
-        prev[level - 1] = &(*curr).levels;
+        prev[level - 1] = &*curr;
 
         unsafe {
             while level > 0 {
-                let mut next = (*curr).levels[level - 1].load_ptr();
+                let next = (&(*curr).levels)[level - 1].load_ptr();
+                let span = (*curr).levels.span(level - 1);
 
-                if !next.is_null() && (*next).levels[level - 1].load_tag() == 1 {
-                    next = Self::unlink_level(curr, next, level - 1);
-                }
-
-                if next.is_null() || (*next).key >= *key {
-                    prev[level - 1] = &(*curr).levels;
+                if next.is_null() || !before(&*next, rank + span) {
+                    prev[level - 1] = &*curr;
+                    ranks[level - 1] = rank;
                     level -= 1;
                 } else {
                     curr = next;
+                    rank += span;
                 }
             }
         }
 
-        let next = (*curr).levels[level].load_ptr();
+        let next = (&(*curr).levels)[level].load_ptr();
 
-        if !next.is_null() && &(*next).key == key {
-            SearchResult {
-                prev,
-                target: unsafe { Some(NonNull::new_unchecked(next)) },
-            }
-        } else {
-            SearchResult { prev, target: None }
+        SearchResult {
+            prev,
+            ranks,
+            target: NonNull::new(next),
+        }
+    }
+
+    /// Finds the last node whose key is `before` the target. See [find_by](SkipList::find_by).
+    fn find_last_by<'a, F>(&'a self, before: F) -> Option<Entry<'a, K, V>>
+    where
+        F: Fn(&K) -> bool,
+    {
+        if self.is_empty() {
+            return None;
+        }
+
+        let SearchResult { prev, .. } = unsafe { self.find_by(before) };
+
+        if self.is_head(prev[0]) {
+            return None;
         }
+
+        Some(Entry::from_raw(NonNull::from(prev[0])))
     }
 
-    pub fn get<'a>(&'a self, key: &K) -> Option<Entry<'a, K, V>> {
+    pub fn get<'a, Q>(&'a self, key: &Q) -> Option<Entry<'a, K, V>>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized,
+        C: Comparator<Q>,
+    {
         if self.is_empty() {
             return None;
         }
 
         // Perform safety check for whether we are dealing with the head.
         unsafe {
-            match self.find(key) {
+            match self.find(key, false) {
                 SearchResult {
                     target: Some(node), ..
                 } => Some(Entry {
@@ -195,13 +498,28 @@ where
         }
     }
 
-    pub fn get_mut<'a>(&'a mut self, key: &K) -> Option<MutEntry<'a, K, V>> {
+    /// Returns `true` if the list contains an entry for the given `key`.
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: ?Sized,
+        C: Comparator<Q>,
+    {
+        self.get(key).is_some()
+    }
+
+    pub fn get_mut<'a, Q>(&'a mut self, key: &Q) -> Option<MutEntry<'a, K, V>>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized,
+        C: Comparator<Q>,
+    {
         if self.is_empty() {
             return None;
         }
 
         unsafe {
-            match self.find(key) {
+            match self.find(key, false) {
                 SearchResult {
                     target: Some(node), ..
                 } => Some(MutEntry {
@@ -213,22 +531,92 @@ where
         }
     }
 
-    fn is_head(&self, ptr: *const Node<K, V>) -> bool {
-        std::ptr::eq(ptr, self.head.as_ptr().cast())
+    /// Returns the first [Entry](Entry) whose key is greater than or equal to `key`.
+    pub fn lower_bound<'a, Q>(&'a self, key: &Q) -> Option<Entry<'a, K, V>>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized,
+        C: Comparator<Q>,
+    {
+        if self.is_empty() {
+            return None;
+        }
+
+        unsafe { self.find(key, true).target.map(Entry::from_raw) }
     }
 
-    fn next_node<'a, E: NodeEntry<K, V>>(&'a self, node: &E) -> Option<E> {
-        if node.levels[0].load_tag() == 1 {
+    /// Returns the first [Entry](Entry) whose key is strictly greater than `key`.
+    pub fn upper_bound<'a, Q>(&'a self, key: &Q) -> Option<Entry<'a, K, V>>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized,
+        C: Comparator<Q>,
+    {
+        if self.is_empty() {
             return None;
         }
 
-        let mut next = node.levels[0].load_ptr();
-
         unsafe {
-            while !next.is_null() && (*next).levels[0].load_tag() == 1 {
-                next = Self::unlink_level(&(*node) as *const _ as *mut Node<K, V>, next, 0);
-            }
+            self.find_by(|k| self.compare(k, key).is_le())
+                .target
+                .map(Entry::from_raw)
         }
+    }
+
+    /// Returns the last [Entry](Entry) whose key is less than or equal to `key`.
+    pub fn floor<'a, Q>(&'a self, key: &Q) -> Option<Entry<'a, K, V>>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized,
+        C: Comparator<Q>,
+    {
+        self.find_last_by(|k| self.compare(k, key).is_le())
+    }
+
+    /// Returns the first [Entry](Entry) whose key is greater than or equal to `key`.
+    ///
+    /// This is the same as [lower_bound](SkipList::lower_bound).
+    pub fn ceiling<'a, Q>(&'a self, key: &Q) -> Option<Entry<'a, K, V>>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized,
+        C: Comparator<Q>,
+    {
+        self.lower_bound(key)
+    }
+
+    /// Returns the [Entry](Entry) at `index`, counting from 0, in O(log n).
+    pub fn nth<'a>(&'a self, index: usize) -> Option<Entry<'a, K, V>> {
+        if index >= self.len() {
+            return None;
+        }
+
+        unsafe { self.find_nth(index).target.map(Entry::from_raw) }
+    }
+
+    /// Returns the index of the first entry for `key`, or `None` if the list does not contain
+    /// `key`. This takes O(log n).
+    pub fn rank<Q>(&self, key: &Q) -> Option<usize>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized,
+        C: Comparator<Q>,
+    {
+        if self.is_empty() {
+            return None;
+        }
+
+        let SearchResult { ranks, target, .. } = unsafe { self.find(key, false) };
+
+        target.map(|_| ranks[0])
+    }
+
+    fn is_head(&self, ptr: *const Node<K, V>) -> bool {
+        std::ptr::eq(ptr, self.head.as_ptr().cast())
+    }
+
+    fn next_node<'a, E: NodeEntry<K, V>>(&'a self, node: &E) -> Option<E> {
+        let next = node.levels[0].load_ptr();
 
         if next.is_null() {
             return None;
@@ -263,56 +651,49 @@ where
         }
     }
 
-    pub fn get_last<'a>(&'a self) -> Option<Entry<'a, K, V>> {
-        let mut curr = self.get_first()?;
-
-        while let Some(next) = self.next_node(&curr) {
-            curr = next;
-        }
+    /// Finds the node preceding `node`. As we do not keep back-links, this requires a search from
+    /// the top of the list.
+    fn prev_node<'a>(&'a self, node: &Entry<'a, K, V>) -> Option<Entry<'a, K, V>> {
+        let mut prev = self.find_last_by(|k| self.compare(k, node.key()).is_lt());
 
-        Some(curr)
-    }
+        // Duplicates of the key in front of `node` precede it as well.
+        loop {
+            let next = match prev.as_ref() {
+                Some(prev) => self.next_node(prev),
+                None => self.get_first(),
+            };
 
-    pub fn get_last_mut<'a>(&'a mut self) -> Option<MutEntry<'a, K, V>> {
-        let mut curr = self.get_first()?;
-
-        while let Some(next) = self.next_node(&curr) {
-            curr = next;
+            match next {
+                Some(next)
+                    if next.node != node.node && self.compare(next.key(), node.key()).is_eq() =>
+                {
+                    prev = Some(next)
+                }
+                _ => return prev,
+            }
         }
-
-        unsafe { Some(core::mem::transmute(curr)) }
     }
 
-    fn traverse_with<F>(&self, mut f: F)
-    where
-        F: FnMut(&K, &V),
-    {
-        let mut curr = unsafe { self.head.as_ref().levels[0].load_ptr() };
-
-        unsafe {
-            while !curr.is_null() {
-                if !(*curr).removed() {
-                    let key = &(*curr).key;
-                    let val = &(*curr).val;
+    pub fn get_last<'a>(&'a self) -> Option<Entry<'a, K, V>> {
+        self.find_last_by(|_| true)
+    }
 
-                    f(key, val);
-                }
+    pub fn get_last_mut<'a>(&'a mut self) -> Option<MutEntry<'a, K, V>> {
+        let last = self.get_last()?;
 
-                curr = (*curr).levels[0].load_ptr();
-            }
-        }
+        unsafe { Some(core::mem::transmute(last)) }
     }
 
-    pub fn entry<'a: 'domain>(&'a mut self, key: K) -> Option<Removable<'a, K, V>> {
+    pub fn entry<'a>(&'a mut self, key: K) -> Option<Removable<'a, K, V, C>> {
         if self.is_empty() {
             return None;
         }
 
         unsafe {
-            match self.find(&key) {
+            match self.find(&key, false) {
                 SearchResult {
-                    prev: _,
                     target: Some(mut target),
+                    ..
                 } => Removable {
                     node: target.as_mut(),
                     list: self,
@@ -324,13 +705,63 @@ where
         }
     }
 
-    pub fn iter<'a>(&'a self) -> iter::Iter<'a, K, V> {
+    pub fn iter<'a>(&'a self) -> iter::Iter<'a, K, V, C> {
         iter::Iter::from_list(self)
     }
 
-    pub fn iter_mut<'a: 'domain>(&'a mut self) -> iter::IterMut<'a, K, V> {
+    pub fn iter_mut<'a>(&'a mut self) -> iter::IterMut<'a, K, V, C> {
         iter::IterMut::from_list(self)
     }
+
+    /// Returns an iterator over all values of `key`, in the order they were inserted. See
+    /// [insert_multi](SkipList::insert_multi).
+    pub fn get_all<'a, 'k, Q>(&'a self, key: &'k Q) -> iter::GetAll<'a, 'k, K, V, Q, C>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized,
+        C: Comparator<Q>,
+    {
+        self.range((Bound::Included(key), Bound::Included(key)))
+    }
+
+    /// Returns an iterator over the entries whose keys lie within `range`.
+    pub fn range<'a, Q, R>(&'a self, range: R) -> iter::Range<'a, K, V, R, Q, C>
+    where
+        K: Borrow<Q>,
+        R: RangeBounds<Q>,
+        Q: ?Sized,
+        C: Comparator<Q>,
+    {
+        iter::Range::from_list(self, range)
+    }
+
+    /// Finds the last [Entry](Entry) that lies before the given `bound`.
+    fn seek_back<'a, Q>(&'a self, bound: Bound<&Q>) -> Option<Entry<'a, K, V>>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized,
+        C: Comparator<Q>,
+    {
+        match bound {
+            Bound::Included(key) => self.floor(key),
+            Bound::Excluded(key) => self.find_last_by(|k| self.compare(k, key).is_lt()),
+            Bound::Unbounded => self.get_last(),
+        }
+    }
+
+    /// Finds the first [Entry](Entry) that lies after the given `bound`.
+    fn seek<'a, Q>(&'a self, bound: Bound<&Q>) -> Option<Entry<'a, K, V>>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized,
+        C: Comparator<Q>,
+    {
+        match bound {
+            Bound::Included(key) => self.lower_bound(key),
+            Bound::Excluded(key) => self.upper_bound(key),
+            Bound::Unbounded => self.get_first(),
+        }
+    }
 }
 
 pub trait NodeEntry<K, V>: core::ops::Deref<Target = Node<K, V>> {
@@ -395,13 +826,13 @@ impl<'a, K, V> NodeEntry<K, V> for MutEntry<'a, K, V> {
     }
 }
 
-pub struct Removable<'a, K, V> {
-    list: &'a mut SkipList<'a, K, V>,
+pub struct Removable<'a, K, V, C = OrdComparator> {
+    list: &'a mut SkipList<K, V, C>,
     node: &'a mut Node<K, V>,
     key: K,
 }
 
-impl<'a, K, V> Removable<'a, K, V> {
+impl<'a, K, V, C> Removable<'a, K, V, C> {
     pub fn val(&self) -> &V {
         &self.node.val
     }
@@ -411,64 +842,149 @@ impl<'a, K, V> Removable<'a, K, V> {
     }
 }
 
-impl<'a, K: Ord, V> Removable<'a, K, V> {
+impl<'a, K, V, C> Removable<'a, K, V, C>
+where
+    C: Comparator<K>,
+{
     pub fn remove(self) -> Option<(K, V)> {
         self.list.remove(&self.key)
     }
 }
 
+impl<K, V, C> crate::skiplist::SkipList<K, V> for SkipList<K, V, C>
+where
+    C: Comparator<K> + Default,
+{
+    type Entry<'a>
+        = Entry<'a, K, V>
+    where
+        Self: 'a;
+
+    type Removed<'a>
+        = (K, V)
+    where
+        Self: 'a;
+
+    type Iter<'a>
+        = iter::Iter<'a, K, V, C>
+    where
+        Self: 'a;
+
+    type Range<'a, R>
+        = iter::Range<'a, K, V, R, K, C>
+    where
+        Self: 'a,
+        R: RangeBounds<K> + 'a;
+
+    fn new() -> Self {
+        Self::with_comparator(C::default())
+    }
+
+    fn insert(&mut self, key: K, value: V) -> Option<(K, V)> {
+        self.internal_insert(key, value, true)
+    }
+
+    fn get<'a>(&'a self, key: &K) -> Option<Entry<'a, K, V>> {
+        SkipList::get(self, key)
+    }
+
+    fn remove(&mut self, key: &K) -> Option<(K, V)> {
+        SkipList::remove(self, key)
+    }
+
+    fn front<'a>(&'a self) -> Option<Entry<'a, K, V>> {
+        self.get_first()
+    }
+
+    fn last<'a>(&'a self) -> Option<Entry<'a, K, V>> {
+        self.get_last()
+    }
+
+    fn iter<'a>(&'a self) -> iter::Iter<'a, K, V, C> {
+        SkipList::iter(self)
+    }
+
+    fn range<'a, R>(&'a self, range: R) -> iter::Range<'a, K, V, R, K, C>
+    where
+        R: RangeBounds<K> + 'a,
+    {
+        SkipList::range(self, range)
+    }
+
+    fn len(&self) -> usize {
+        SkipList::len(self)
+    }
+}
+
+impl<'a, K, V> crate::skiplist::Entry<'a, K, V> for Entry<'a, K, V> {
+    fn val(&self) -> &V {
+        Entry::val(self)
+    }
+
+    fn key(&self) -> &K {
+        Entry::key(self)
+    }
+}
+
 struct SearchResult<'a, K, V> {
-    prev: [&'a Levels<K, V>; HEIGHT],
+    prev: [&'a Node<K, V>; HEIGHT],
+    ranks: [usize; HEIGHT],
     target: Option<NonNull<Node<K, V>>>,
 }
 
 pub mod iter {
     use super::{Entry, MutEntry, SkipList};
-    use core::iter::Iterator;
-
-    pub struct Iter<'a, K, V> {
-        list: &'a SkipList<'a, K, V>,
-        next: Option<Entry<'a, K, V>>,
+    use crate::comparator::{Comparator, OrdComparator};
+    use crate::internal::utils::before_end;
+    use core::borrow::Borrow;
+    use core::iter::{DoubleEndedIterator, Iterator};
+    use core::marker::PhantomData;
+    use core::ops::{Bound, RangeBounds, RangeFull};
+
+    pub struct Iter<'a, K, V, C = OrdComparator> {
+        range: Range<'a, K, V, RangeFull, K, C>,
     }
 
-    impl<'a, K, V> Iter<'a, K, V>
+    impl<'a, K, V, C> Iter<'a, K, V, C>
     where
-        K: Ord,
+        C: Comparator<K>,
     {
-        pub fn from_list(list: &'a SkipList<'a, K, V>) -> Self {
+        pub fn from_list(list: &'a SkipList<K, V, C>) -> Self {
             Iter {
-                list,
-                next: list.get_first(),
+                range: Range::from_list(list, ..),
             }
         }
     }
 
-    impl<'a, K, V> Iterator for Iter<'a, K, V>
+    impl<'a, K, V, C> Iterator for Iter<'a, K, V, C>
     where
-        K: Ord,
+        C: Comparator<K>,
     {
         type Item = Entry<'a, K, V>;
         fn next(&mut self) -> Option<Self::Item> {
-            if let Some(next) = self.next.take() {
-                self.next = self.list.next_node(&next);
+            self.range.next()
+        }
+    }
 
-                Some(next)
-            } else {
-                None
-            }
+    impl<'a, K, V, C> DoubleEndedIterator for Iter<'a, K, V, C>
+    where
+        C: Comparator<K>,
+    {
+        fn next_back(&mut self) -> Option<Self::Item> {
+            self.range.next_back()
         }
     }
 
-    pub struct IterMut<'a, K, V> {
-        list: &'a SkipList<'a, K, V>,
+    pub struct IterMut<'a, K, V, C = OrdComparator> {
+        list: &'a SkipList<K, V, C>,
         next: Option<Entry<'a, K, V>>,
     }
 
-    impl<'a, K, V> IterMut<'a, K, V>
+    impl<'a, K, V, C> IterMut<'a, K, V, C>
     where
-        K: Ord,
+        C: Comparator<K>,
     {
-        pub fn from_list(list: &'a mut SkipList<'a, K, V>) -> Self {
+        pub fn from_list(list: &'a mut SkipList<K, V, C>) -> Self {
             IterMut {
                 list: &(*list),
                 next: list.get_first(),
@@ -476,9 +992,9 @@ pub mod iter {
         }
     }
 
-    impl<'a, K, V> Iterator for IterMut<'a, K, V>
+    impl<'a, K, V, C> Iterator for IterMut<'a, K, V, C>
     where
-        K: Ord,
+        C: Comparator<K>,
     {
         type Item = MutEntry<'a, K, V>;
         fn next(&mut self) -> Option<Self::Item> {
@@ -492,22 +1008,131 @@ pub mod iter {
         }
     }
 
-    pub struct IntoIter<'a, K, V> {
-        list: SkipList<'a, K, V>,
+    /// The [Range](Range) over all values of a single key, see
+    /// [get_all](SkipList::get_all).
+    pub type GetAll<'a, 'k, K, V, Q, C = OrdComparator> =
+        Range<'a, K, V, (Bound<&'k Q>, Bound<&'k Q>), Q, C>;
+
+    pub struct Range<'a, K, V, R, Q: ?Sized = K, C = OrdComparator> {
+        list: &'a SkipList<K, V, C>,
+        next: Option<Entry<'a, K, V>>,
+        next_back: Option<Entry<'a, K, V>>,
+        // The back of the range is only searched for once we start iterating from it.
+        back_started: bool,
+        range: R,
+        _bound: PhantomData<fn(&Q)>,
     }
 
-    impl<'a, K, V> IntoIter<'a, K, V>
+    impl<'a, K, V, R, Q, C> Range<'a, K, V, R, Q, C>
     where
-        K: Ord,
+        K: Borrow<Q>,
+        R: RangeBounds<Q>,
+        Q: ?Sized,
+        C: Comparator<K> + Comparator<Q>,
     {
-        pub fn from_list(list: SkipList<'a, K, V>) -> Self {
+        pub fn from_list(list: &'a SkipList<K, V, C>, range: R) -> Self {
+            Range {
+                list,
+                next: list.seek(range.start_bound()),
+                next_back: None,
+                back_started: false,
+                range,
+                _bound: PhantomData,
+            }
+        }
+    }
+
+    impl<'a, K, V, R, Q, C> Iterator for Range<'a, K, V, R, Q, C>
+    where
+        K: Borrow<Q>,
+        R: RangeBounds<Q>,
+        Q: ?Sized,
+        C: Comparator<K> + Comparator<Q>,
+    {
+        type Item = Entry<'a, K, V>;
+        fn next(&mut self) -> Option<Self::Item> {
+            let next = self.next.take()?;
+
+            if !before_end(&self.list.comparator, &self.range, next.key()) {
+                return None;
+            }
+
+            if self.back_started {
+                // Once both ends meet, the range is exhausted.
+                let back = self.next_back.as_ref()?;
+
+                let order = Comparator::<K>::compare(&self.list.comparator, next.key(), back.key());
+
+                if order.is_gt() {
+                    self.next_back = None;
+                    return None;
+                }
+
+                if next.node == back.node {
+                    self.next_back = None;
+                    return Some(next);
+                }
+            }
+
+            self.next = self.list.next_node(&next);
+
+            Some(next)
+        }
+    }
+
+    impl<'a, K, V, R, Q, C> DoubleEndedIterator for Range<'a, K, V, R, Q, C>
+    where
+        K: Borrow<Q>,
+        R: RangeBounds<Q>,
+        Q: ?Sized,
+        C: Comparator<K> + Comparator<Q>,
+    {
+        fn next_back(&mut self) -> Option<Self::Item> {
+            if !self.back_started {
+                self.back_started = true;
+                self.next_back = self.list.seek_back(self.range.end_bound());
+            }
+
+            let back = self.next_back.take()?;
+
+            // The front always lies within the start bound, so we only have to make sure we do
+            // not walk past it.
+            let next = self.next.as_ref()?;
+
+            let order = Comparator::<K>::compare(&self.list.comparator, back.key(), next.key());
+
+            if order.is_lt() {
+                self.next = None;
+                return None;
+            }
+
+            if back.node == next.node {
+                self.next = None;
+                return Some(back);
+            }
+
+            self.next_back = self.list.prev_node(&back);
+
+            Some(back)
+        }
+    }
+
+    pub struct IntoIter<K, V, C = OrdComparator> {
+        list: SkipList<K, V, C>,
+    }
+
+    impl<K, V, C> IntoIter<K, V, C>
+    where
+        C: Comparator<K>,
+    {
+        pub fn from_list(list: SkipList<K, V, C>) -> Self {
             IntoIter { list }
         }
     }
 
-    impl<'a, K, V> Iterator for IntoIter<'a, K, V>
+    impl<K, V, C> Iterator for IntoIter<K, V, C>
     where
-        K: Ord,
+        C: Comparator<K>,
     {
         type Item = (K, V);
         fn next(&mut self) -> Option<Self::Item> {
@@ -515,12 +1140,12 @@ pub mod iter {
         }
     }
 
-    impl<'a, K, V> core::iter::IntoIterator for SkipList<'a, K, V>
+    impl<K, V, C> core::iter::IntoIterator for SkipList<K, V, C>
     where
-        K: Ord,
+        C: Comparator<K>,
     {
         type Item = (K, V);
-        type IntoIter = IntoIter<'a, K, V>;
+        type IntoIter = IntoIter<K, V, C>;
 
         fn into_iter(self) -> Self::IntoIter {
             IntoIter::from_list(self)
@@ -532,6 +1157,43 @@ pub mod iter {
 mod skiplist_test {
     use super::*;
 
+    /// Checks that the span of every level equals the number of nodes it skips on the bottom
+    /// level, or the number of nodes that follow if it is the last one of its level.
+    fn spans_consistent<K, V, C>(list: &SkipList<K, V, C>) -> bool {
+        let head = list.head.as_ptr().cast::<Node<K, V>>();
+        let mut ranks = std::collections::HashMap::from([(head, 0)]);
+
+        unsafe {
+            let mut node = (&(*head).levels)[0].load_ptr();
+
+            while !node.is_null() {
+                ranks.insert(node, ranks.len());
+                node = (&(*node).levels)[0].load_ptr();
+            }
+
+            let len = ranks.len() - 1;
+
+            (0..HEIGHT).all(|i| {
+                let mut curr = head;
+
+                loop {
+                    let next = (&(*curr).levels)[i].load_ptr();
+                    let until = if next.is_null() { len } else { ranks[&next] };
+
+                    if (*curr).levels.span(i) != until - ranks[&curr] {
+                        return false;
+                    }
+
+                    if next.is_null() {
+                        return true;
+                    }
+
+                    curr = next;
+                }
+            })
+        }
+    }
+
     #[test]
     fn test_new_node() {
         let node = Node::new(100, "hello", 1);
@@ -552,7 +1214,7 @@ mod skiplist_test {
 
     #[test]
     fn test_new_list() {
-        let _: SkipList<'_, usize, usize> = SkipList::new();
+        let _: SkipList<usize, usize> = SkipList::new();
     }
 
     #[test]
@@ -570,7 +1232,7 @@ mod skiplist_test {
 
     #[test]
     fn test_rand_height() {
-        let mut list: SkipList<'_, i32, i32> = SkipList::new();
+        let mut list: SkipList<i32, i32> = SkipList::new();
         let node = Node::new_rand_height("Hello", "There!", &mut list);
 
         assert!(!node.is_null());
@@ -621,6 +1283,8 @@ mod skiplist_test {
             rng ^= rng << 7;
             list.remove(&rng);
         }
+
+        debug_assert!(spans_consistent(&list));
     }
 
     #[test]
@@ -662,11 +1326,183 @@ mod skiplist_test {
 
         let mut prev = list.get_first().unwrap().key().clone();
 
-        list.traverse_with(|k, _| {
-            println!("key: {:?}", k);
-            assert!(*k >= prev);
-            prev = k.clone();
-        })
+        for entry in list.iter() {
+            println!("key: {:?}", entry.key());
+            assert!(*entry.key() >= prev);
+            prev = entry.key().clone();
+        }
+    }
+
+    #[test]
+    fn test_range() {
+        let mut list = SkipList::new();
+
+        for i in 0..100 {
+            list.insert(i, i * 2);
+        }
+
+        let keys = |range| list.range(range).map(|e| *e.key()).collect::<Vec<_>>();
+
+        assert_eq!(
+            keys((Bound::Included(10), Bound::Excluded(15))),
+            vec![10, 11, 12, 13, 14]
+        );
+        assert_eq!(
+            keys((Bound::Excluded(10), Bound::Included(12))),
+            vec![11, 12]
+        );
+        assert_eq!(
+            keys((Bound::Included(97), Bound::Unbounded)),
+            vec![97, 98, 99]
+        );
+        assert_eq!(keys((Bound::Unbounded, Bound::Excluded(3))), vec![0, 1, 2]);
+        assert_eq!(keys((Bound::Included(120), Bound::Unbounded)), vec![]);
+        assert_eq!(keys((Bound::Included(50), Bound::Excluded(50))), vec![]);
+
+        list.remove(&21);
+
+        assert_eq!(
+            list.range(20..23).map(|e| *e.val()).collect::<Vec<_>>(),
+            vec![40, 44]
+        );
+        assert_eq!(list.range(..).count(), 99);
+    }
+
+    #[test]
+    fn test_rev() {
+        let mut list = SkipList::new();
+
+        for i in 0..100 {
+            list.insert(i, ());
+        }
+
+        assert_eq!(*list.get_last().unwrap().key(), 99);
+        assert_eq!(
+            list.iter()
+                .rev()
+                .take(3)
+                .map(|e| *e.key())
+                .collect::<Vec<_>>(),
+            vec![99, 98, 97]
+        );
+        assert_eq!(list.iter().rev().count(), 100);
+        assert_eq!(
+            list.range(10..15)
+                .rev()
+                .map(|e| *e.key())
+                .collect::<Vec<_>>(),
+            vec![14, 13, 12, 11, 10]
+        );
+        assert_eq!(list.range(150..).rev().count(), 0);
+
+        let mut range = list.range(40..45);
+
+        assert_eq!(range.next().map(|e| *e.key()), Some(40));
+        assert_eq!(range.next_back().map(|e| *e.key()), Some(44));
+        assert_eq!(range.next_back().map(|e| *e.key()), Some(43));
+        assert_eq!(range.next().map(|e| *e.key()), Some(41));
+        assert_eq!(range.next().map(|e| *e.key()), Some(42));
+        assert!(range.next_back().is_none());
+        assert!(range.next().is_none());
+
+        list.remove(&99);
+
+        assert_eq!(*list.get_last().unwrap().key(), 98);
+    }
+
+    #[test]
+    fn test_bounds() {
+        let mut list = SkipList::new();
+
+        for i in (0..100).step_by(10) {
+            list.insert(i, ());
+        }
+
+        assert_eq!(list.lower_bound(&20).map(|e| *e.key()), Some(20));
+        assert_eq!(list.lower_bound(&21).map(|e| *e.key()), Some(30));
+        assert_eq!(list.upper_bound(&20).map(|e| *e.key()), Some(30));
+        assert_eq!(list.upper_bound(&25).map(|e| *e.key()), Some(30));
+        assert_eq!(list.floor(&20).map(|e| *e.key()), Some(20));
+        assert_eq!(list.floor(&29).map(|e| *e.key()), Some(20));
+        assert_eq!(list.ceiling(&29).map(|e| *e.key()), Some(30));
+
+        assert!(list.floor(&-1).is_none());
+        assert_eq!(list.floor(&1_000).map(|e| *e.key()), Some(90));
+        assert_eq!(list.ceiling(&-1).map(|e| *e.key()), Some(0));
+        assert!(list.ceiling(&91).is_none());
+        assert!(list.upper_bound(&90).is_none());
+    }
+
+    #[test]
+    fn test_borrowed_lookup() {
+        let mut list = SkipList::new();
+
+        for word in ["apple", "banana", "cherry", "date"] {
+            list.insert(word.to_string(), word.len());
+        }
+
+        assert_eq!(list.get("banana").map(|e| *e.val()), Some(6));
+        assert!(list.contains_key("cherry"));
+        assert!(!list.contains_key("fig"));
+        assert_eq!(
+            list.lower_bound("c").map(|e| e.key().as_str()),
+            Some("cherry")
+        );
+        assert_eq!(
+            list.range::<str, _>((Bound::Included("b"), Bound::Excluded("d")))
+                .map(|e| e.key().as_str())
+                .collect::<Vec<_>>(),
+            vec!["banana", "cherry"]
+        );
+        assert_eq!(list.remove("apple"), Some(("apple".to_string(), 5)));
+        assert!(!list.contains_key("apple"));
+    }
+
+    #[test]
+    fn test_comparator() {
+        let mut list = SkipList::with_comparator(|a: &u8, b: &u8| b.cmp(a));
+
+        for i in 0..10 {
+            list.insert(i, ());
+        }
+
+        assert_eq!(
+            list.iter().map(|e| *e.key()).collect::<Vec<_>>(),
+            vec![9, 8, 7, 6, 5, 4, 3, 2, 1, 0]
+        );
+        assert_eq!(list.floor(&4).map(|e| *e.key()), Some(4));
+        assert_eq!(list.upper_bound(&4).map(|e| *e.key()), Some(3));
+        assert_eq!(list.remove_first(), Some((9, ())));
+        assert_eq!(list.get_last().map(|e| *e.key()), Some(0));
+    }
+
+    #[test]
+    fn test_multi() {
+        let mut list = SkipList::new();
+
+        for (key, val) in [(2, "a"), (1, "b"), (2, "c"), (3, "d"), (2, "e")] {
+            list.insert_multi(key, val);
+        }
+
+        assert_eq!(list.len(), 5);
+        assert_eq!(
+            list.get_all(&2).map(|e| *e.val()).collect::<Vec<_>>(),
+            vec!["a", "c", "e"]
+        );
+        assert_eq!(
+            list.iter().rev().map(|e| *e.val()).collect::<Vec<_>>(),
+            vec!["d", "e", "c", "a", "b"]
+        );
+        assert_eq!(
+            list.get_all(&2).rev().map(|e| *e.val()).collect::<Vec<_>>(),
+            vec!["e", "c", "a"]
+        );
+
+        assert_eq!(list.remove_one(&2), Some((2, "a")));
+        assert_eq!(list.get(&2).map(|e| *e.val()), Some("c"));
+        assert_eq!(list.remove_all(&2), 2);
+        assert_eq!(list.get_all(&2).count(), 0);
+        assert_eq!(list.len(), 2);
     }
 
     #[test]
@@ -680,4 +1516,130 @@ mod skiplist_test {
 
         println!("{}", list.get_last().unwrap().key())
     }
+
+    #[test]
+    fn test_from_sorted_iter() {
+        let list = SkipList::<u32, u32>::from_sorted_iter((0..1_000).map(|i| (i * 2, i)));
+
+        assert_eq!(list.len(), 1_000);
+        assert!(list.iter().map(|e| *e.key()).eq((0..1_000).map(|i| i * 2)));
+        assert_eq!(list.get(&500).map(|e| *e.val()), Some(250));
+        assert_eq!(list.nth(999).map(|e| *e.key()), Some(1_998));
+        assert_eq!(list.rank(&1_000), Some(500));
+
+        debug_assert!(spans_consistent(&list));
+
+        let mut list = list;
+        list.insert(1, 1);
+        assert_eq!(list.rank(&2), Some(2));
+        assert_eq!(list.remove_nth(0), Some((0, 0)));
+        assert_eq!(list.get_last().map(|e| *e.key()), Some(1_998));
+        debug_assert!(spans_consistent(&list));
+
+        let empty = SkipList::<u32, u32>::from_sorted_iter(core::iter::empty());
+        assert!(empty.is_empty());
+    }
+
+    #[test]
+    #[should_panic]
+    #[cfg(debug_assertions)]
+    fn test_from_unsorted_iter() {
+        let _ = SkipList::<u32, ()>::from_sorted_iter([(2, ()), (1, ())]);
+    }
+
+    #[test]
+    fn test_split_off() {
+        let mut list = SkipList::<u32, u32>::from_sorted_iter((0..1_000).map(|i| (i, i)));
+
+        let mut other = list.split_off(&600);
+
+        assert_eq!(list.len(), 600);
+        assert_eq!(other.len(), 400);
+        assert!(list.iter().map(|e| *e.key()).eq(0..600));
+        assert!(other.iter().map(|e| *e.key()).eq(600..1_000));
+        assert_eq!(list.get_last().map(|e| *e.key()), Some(599));
+        assert_eq!(other.nth(0).map(|e| *e.key()), Some(600));
+        assert_eq!(other.rank(&999), Some(399));
+        debug_assert!(spans_consistent(&list));
+        debug_assert!(spans_consistent(&other));
+
+        assert_eq!(list.split_off(&1_000).len(), 0);
+        assert_eq!(list.split_off(&0).len(), 600);
+        assert!(list.is_empty());
+
+        list.insert(0, 0);
+        list.append(&mut other);
+
+        assert!(other.is_empty());
+        assert!(other.get_first().is_none());
+        assert_eq!(list.len(), 401);
+        assert_eq!(list.rank(&700), Some(101));
+        assert_eq!(list.remove_nth(400), Some((999, 999)));
+        assert_eq!(list.get_last().map(|e| *e.key()), Some(998));
+        debug_assert!(spans_consistent(&list));
+
+        // overlapping lists are merged entry by entry
+        let mut other = SkipList::from_sorted_iter([(1, 1), (700, 0)]);
+        list.append(&mut other);
+
+        assert!(other.is_empty());
+        assert_eq!(list.len(), 402);
+        assert_eq!(list.nth(1).map(|e| *e.key()), Some(1));
+        assert_eq!(list.nth(102).map(|e| *e.val()), Some(700));
+        assert_eq!(list.nth(103).map(|e| (*e.key(), *e.val())), Some((700, 0)));
+        debug_assert!(spans_consistent(&list));
+    }
+
+    #[test]
+    fn test_nth() {
+        let mut list = SkipList::new();
+        let mut keys = Vec::new();
+
+        for _ in 0..1_000 {
+            let key = rand::random::<u16>();
+            list.insert(key, ());
+            keys.push(key);
+        }
+
+        keys.sort_unstable();
+        keys.dedup();
+
+        debug_assert!(spans_consistent(&list));
+
+        for (i, key) in keys.iter().enumerate() {
+            assert_eq!(list.nth(i).map(|e| *e.key()), Some(*key));
+            assert_eq!(list.rank(key), Some(i));
+        }
+
+        assert!(list.nth(keys.len()).is_none());
+
+        while !keys.is_empty() {
+            let i = rand::random::<usize>() % keys.len();
+            let key = keys.remove(i);
+
+            assert_eq!(list.remove_nth(i), Some((key, ())));
+            assert_eq!(list.rank(&key), None);
+
+            if let Some(key) = keys.get(i) {
+                assert_eq!(list.rank(key), Some(i));
+            }
+
+            debug_assert!(spans_consistent(&list));
+        }
+
+        assert!(list.is_empty());
+        assert!(list.remove_nth(0).is_none());
+
+        let mut list = SkipList::new();
+
+        for (key, val) in [(1, "a"), (2, "b"), (1, "c"), (0, "d")] {
+            list.insert_multi(key, val);
+        }
+
+        assert_eq!(list.rank(&1), Some(1));
+        assert_eq!(list.nth(2).map(|e| *e.val()), Some("c"));
+        assert_eq!(list.remove_nth(1), Some((1, "a")));
+        assert_eq!(list.nth(2).map(|e| *e.val()), Some("b"));
+        debug_assert!(spans_consistent(&list));
+    }
 }
@@ -0,0 +1,89 @@
+use core::borrow::Borrow;
+
+use super::{Entry, SkipList};
+use crate::comparator::{Comparator, OrdComparator};
+
+/// A cursor over a [SkipList](SkipList) that can move back and forth between its entries.
+///
+/// Besides pointing at an [Entry](Entry), a cursor may be unpositioned, which it is when it is
+/// first created or after it moved past either end of the list. From there, [next](Cursor::next)
+/// moves to the first and [prev](Cursor::prev) to the last entry of the list.
+///
+/// # Concurrent removals
+///
+/// The current entry stays protected for as long as the cursor points at it, so
+/// [key](Cursor::key) and [value](Cursor::value) remain valid even if another thread removes it.
+/// Movement is always resolved through the key of the current entry: `next` moves to the first
+/// live entry whose key is greater than it, and `prev` to the last live entry whose key is less
+/// than it.
+pub struct Cursor<'a, K, V, C = OrdComparator> {
+    list: &'a SkipList<K, V, C>,
+    current: Option<Entry<'a, K, V>>,
+}
+
+impl<'a, K, V, C> Cursor<'a, K, V, C>
+where
+    K: Send + Sync,
+    V: Send + Sync,
+    C: Comparator<K>,
+{
+    pub(crate) fn new(list: &'a SkipList<K, V, C>) -> Self {
+        Cursor {
+            list,
+            current: None,
+        }
+    }
+
+    /// Moves the cursor to the first entry whose key is greater than or equal to `key`.
+    pub fn seek<Q>(&mut self, key: &Q) -> Option<&Entry<'a, K, V>>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized,
+        C: Comparator<Q>,
+    {
+        self.current = self.list.lower_bound(key);
+        self.current.as_ref()
+    }
+
+    /// Moves the cursor to the following entry, or to the first one if it is unpositioned.
+    #[allow(clippy::should_implement_trait)]
+    pub fn next(&mut self) -> Option<&Entry<'a, K, V>> {
+        self.current = match self.current.take() {
+            Some(current) => self.list.next_node(&current),
+            None => self.list.get_first(),
+        };
+
+        self.current.as_ref()
+    }
+
+    /// Moves the cursor to the preceding entry, or to the last one if it is unpositioned.
+    pub fn prev(&mut self) -> Option<&Entry<'a, K, V>> {
+        self.current = match self.current.take() {
+            Some(current) => self.list.prev_node(&current),
+            None => self.list.get_last(),
+        };
+
+        self.current.as_ref()
+    }
+
+    /// Returns the [Entry](Entry) the cursor points at.
+    pub fn current(&self) -> Option<&Entry<'a, K, V>> {
+        self.current.as_ref()
+    }
+
+    pub fn key(&self) -> Option<&K> {
+        self.current.as_ref().map(|current| current.key())
+    }
+
+    pub fn value(&self) -> Option<&V> {
+        self.current.as_ref().map(|current| current.val())
+    }
+
+    /// Removes the current entry from the list, unless another thread has already removed it.
+    ///
+    /// The cursor keeps pointing at the removed entry, so moving on from it follows the same
+    /// rules as for entries removed by other threads.
+    pub fn remove_current(&mut self) -> Option<Entry<'a, K, V>> {
+        self.list.remove_entry(self.current.as_ref()?)
+    }
+}
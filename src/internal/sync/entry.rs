@@ -0,0 +1,96 @@
+use super::{Entry, SkipList};
+use crate::comparator::{Comparator, OrdComparator};
+
+/// A view into a single key of a [SkipList](SkipList), which may either be occupied or vacant.
+pub enum MapEntry<'a, K, V, C = OrdComparator> {
+    Occupied(Entry<'a, K, V>),
+    Vacant(VacantEntry<'a, K, V, C>),
+}
+
+impl<'a, K, V, C> MapEntry<'a, K, V, C>
+where
+    K: Send + Sync,
+    V: Send + Sync,
+    C: Comparator<K>,
+{
+    pub fn key(&self) -> &K {
+        match self {
+            MapEntry::Occupied(entry) => entry.key(),
+            MapEntry::Vacant(entry) => entry.key(),
+        }
+    }
+
+    /// Returns the occupying [Entry](Entry), or inserts `val` if the key is vacant.
+    pub fn or_insert(self, val: V) -> Entry<'a, K, V> {
+        match self {
+            MapEntry::Occupied(entry) => entry,
+            MapEntry::Vacant(entry) => entry.insert(val),
+        }
+    }
+
+    /// Returns the occupying [Entry](Entry), or inserts the result of `f` if the key is vacant.
+    pub fn or_insert_with<F>(self, f: F) -> Entry<'a, K, V>
+    where
+        F: FnOnce() -> V,
+    {
+        match self {
+            MapEntry::Occupied(entry) => entry,
+            MapEntry::Vacant(entry) => entry.insert_with(f),
+        }
+    }
+
+    /// Calls `f` with the value of an occupied entry.
+    ///
+    /// Values are shared between threads, so `f` can only modify them through interior
+    /// mutability, e.g. atomics.
+    pub fn and_modify<F>(self, f: F) -> Self
+    where
+        F: FnOnce(&V),
+    {
+        if let MapEntry::Occupied(entry) = &self {
+            f(entry.val());
+        }
+
+        self
+    }
+}
+
+/// A key that had no live value in the [SkipList](SkipList) when it was looked up.
+pub struct VacantEntry<'a, K, V, C = OrdComparator> {
+    list: &'a SkipList<K, V, C>,
+    key: K,
+}
+
+impl<'a, K, V, C> VacantEntry<'a, K, V, C>
+where
+    K: Send + Sync,
+    V: Send + Sync,
+    C: Comparator<K>,
+{
+    pub(crate) fn new(list: &'a SkipList<K, V, C>, key: K) -> Self {
+        VacantEntry { list, key }
+    }
+
+    pub fn key(&self) -> &K {
+        &self.key
+    }
+
+    pub fn into_key(self) -> K {
+        self.key
+    }
+
+    /// Inserts `val` for the vacant key.
+    ///
+    /// If another thread has inserted the key in the meantime, its [Entry](Entry) is returned
+    /// instead and `val` is dropped. See [get_or_insert](SkipList::get_or_insert).
+    pub fn insert(self, val: V) -> Entry<'a, K, V> {
+        self.list.get_or_insert(self.key, val)
+    }
+
+    fn insert_with<F>(self, f: F) -> Entry<'a, K, V>
+    where
+        F: FnOnce() -> V,
+    {
+        self.list.get_or_insert_with(self.key, f)
+    }
+}
@@ -1,46 +1,245 @@
-use crate::internal::utils::Node;
+use crate::internal::utils::{before_end, Node};
 
 use super::{Entry, SkipList};
-use core::iter::{FromIterator, IntoIterator, Iterator};
+use crate::comparator::{Comparator, OrdComparator};
+use core::borrow::Borrow;
+use core::iter::{DoubleEndedIterator, FromIterator, IntoIterator};
+use core::marker::PhantomData;
+use core::ops::{Bound, RangeBounds, RangeFull};
 
-pub struct Iter<'a, K, V> {
-    list: &'a SkipList<'a, K, V>,
+pub struct Iter<'a, K, V, C = OrdComparator> {
+    range: Range<'a, K, V, RangeFull, K, C>,
+}
+
+impl<'a, K, V, C> Iter<'a, K, V, C>
+where
+    K: Send + Sync,
+    V: Send + Sync,
+    C: Comparator<K>,
+{
+    pub fn from_list(list: &'a SkipList<K, V, C>) -> Self {
+        Self {
+            range: Range::from_list(list, ..),
+        }
+    }
+}
+
+impl<'a, K, V, C> core::iter::Iterator for Iter<'a, K, V, C>
+where
+    K: Send + Sync,
+    V: Send + Sync,
+    C: Comparator<K>,
+{
+    type Item = Entry<'a, K, V>;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.range.next()
+    }
+}
+
+impl<'a, K, V, C> DoubleEndedIterator for Iter<'a, K, V, C>
+where
+    K: Send + Sync,
+    V: Send + Sync,
+    C: Comparator<K>,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.range.next_back()
+    }
+}
+
+/// The [Range](Range) over all values of a single key, see [get_all](SkipList::get_all).
+pub type GetAll<'a, 'k, K, V, Q, C = OrdComparator> =
+    Range<'a, K, V, (Bound<&'k Q>, Bound<&'k Q>), Q, C>;
+
+pub struct Range<'a, K, V, R, Q: ?Sized = K, C = OrdComparator> {
+    list: &'a SkipList<K, V, C>,
+    next: Option<Entry<'a, K, V>>,
+    next_back: Option<Entry<'a, K, V>>,
+    /// The entries preceding `next_back` that share its key, see
+    /// [prev_run](SkipList::prev_run).
+    back_run: Vec<Entry<'a, K, V>>,
+    // The back of the range is only searched for once we start iterating from it.
+    back_started: bool,
+    range: R,
+    _bound: PhantomData<fn(&Q)>,
+}
+
+impl<'a, K, V, R, Q, C> Range<'a, K, V, R, Q, C>
+where
+    K: Send + Sync,
+    V: Send + Sync,
+    K: Borrow<Q>,
+    R: RangeBounds<Q>,
+    Q: ?Sized,
+    C: Comparator<K> + Comparator<Q>,
+{
+    pub fn from_list(list: &'a SkipList<K, V, C>, range: R) -> Self {
+        Self {
+            list,
+            next: list.seek(range.start_bound()),
+            next_back: None,
+            back_run: Vec::new(),
+            back_started: false,
+            range,
+            _bound: PhantomData,
+        }
+    }
+}
+
+impl<'a, K, V, R, Q, C> core::iter::Iterator for Range<'a, K, V, R, Q, C>
+where
+    K: Send + Sync,
+    V: Send + Sync,
+    K: Borrow<Q>,
+    R: RangeBounds<Q>,
+    Q: ?Sized,
+    C: Comparator<K> + Comparator<Q>,
+{
+    type Item = Entry<'a, K, V>;
+    fn next(&mut self) -> Option<Self::Item> {
+        let next = self.next.take()?;
+
+        if !before_end(&self.list.comparator, &self.range, next.key()) {
+            return None;
+        }
+
+        if self.back_started {
+            // Once both ends meet, the range is exhausted.
+            let back = self.next_back.as_ref()?;
+
+            let order = Comparator::<K>::compare(&self.list.comparator, next.key(), back.key());
+
+            if order.is_gt() {
+                self.next_back = None;
+                return None;
+            }
+
+            if next.is(back) {
+                self.next_back = None;
+                return Some(next);
+            }
+        }
+
+        self.next = self.list.next_node(&next);
+        Some(next)
+    }
+}
+
+impl<'a, K, V, R, Q, C> DoubleEndedIterator for Range<'a, K, V, R, Q, C>
+where
+    K: Send + Sync,
+    V: Send + Sync,
+    K: Borrow<Q>,
+    R: RangeBounds<Q>,
+    Q: ?Sized,
+    C: Comparator<K> + Comparator<Q>,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if !self.back_started {
+            self.back_started = true;
+            self.next_back = self.list.seek_back(self.range.end_bound());
+        }
+
+        let back = self.next_back.take()?;
+
+        // The front always lies within the start bound, so we only have to make sure we do not
+        // walk past it.
+        let next = self.next.as_ref()?;
+
+        let order = Comparator::<K>::compare(&self.list.comparator, back.key(), next.key());
+
+        if order.is_lt() {
+            self.next = None;
+            return None;
+        }
+
+        if back.is(next) {
+            self.next = None;
+            return Some(back);
+        }
+
+        if self.back_run.is_empty() {
+            self.back_run = self.list.prev_run(&back);
+        }
+
+        self.next_back = self.back_run.pop();
+        Some(back)
+    }
+}
+
+/// An iterator that removes the entries matching a predicate. See
+/// [extract_if](SkipList::extract_if).
+pub struct ExtractIf<'a, K, V, F, C = OrdComparator>
+where
+    K: Send + Sync,
+    V: Send + Sync,
+    C: Comparator<K>,
+{
+    list: &'a SkipList<K, V, C>,
     next: Option<Entry<'a, K, V>>,
+    pred: F,
+    removed: bool,
 }
 
-impl<'a, K, V> Iter<'a, K, V>
+impl<'a, K, V, F, C> ExtractIf<'a, K, V, F, C>
 where
-    K: Ord + Send + Sync,
+    K: Send + Sync,
     V: Send + Sync,
+    F: FnMut(&K, &V) -> bool,
+    C: Comparator<K>,
 {
-    pub fn from_list(list: &'a SkipList<'a, K, V>) -> Self {
+    pub(crate) fn new(list: &'a SkipList<K, V, C>, pred: F) -> Self {
         Self {
             list,
             next: list.get_first(),
+            pred,
+            removed: false,
         }
     }
 }
 
-impl<'a, K, V> core::iter::Iterator for Iter<'a, K, V>
+impl<'a, K, V, F, C> core::iter::Iterator for ExtractIf<'a, K, V, F, C>
 where
-    K: Ord + Send + Sync,
+    K: Send + Sync,
     V: Send + Sync,
+    F: FnMut(&K, &V) -> bool,
+    C: Comparator<K>,
 {
     type Item = Entry<'a, K, V>;
     fn next(&mut self) -> Option<Self::Item> {
-        if let Some(next) = self.next.take() {
+        loop {
+            let next = self.next.take()?;
+
+            // We move on before removing `next`, as the successor of a removed node can only be
+            // found through another search.
             self.next = self.list.next_node(&next);
-            return Some(next);
+
+            if (self.pred)(next.key(), next.val()) && self.list.mark_removed(&next) {
+                self.removed = true;
+                return Some(next);
+            }
         }
+    }
+}
 
-        None
+impl<'a, K, V, F, C> Drop for ExtractIf<'a, K, V, F, C>
+where
+    K: Send + Sync,
+    V: Send + Sync,
+    C: Comparator<K>,
+{
+    fn drop(&mut self) {
+        if self.removed {
+            self.list.unlink_all_removed();
+        }
     }
 }
 
-impl<'a, K, V> IntoIterator for SkipList<'a, K, V>
+impl<K, V, C> IntoIterator for SkipList<K, V, C>
 where
-    K: Ord + Send + Sync,
+    K: Send + Sync,
     V: Send + Sync,
+    C: Comparator<K>,
 {
     type Item = (K, V);
     type IntoIter = IntoIter<K, V>;
@@ -49,13 +248,14 @@ where
     }
 }
 
-impl<'a, K, V> FromIterator<(K, V)> for SkipList<'a, K, V>
+impl<K, V, C> FromIterator<(K, V)> for SkipList<K, V, C>
 where
-    K: Ord + Send + Sync,
+    K: Send + Sync,
     V: Send + Sync,
+    C: Comparator<K> + Default,
 {
     fn from_iter<T: IntoIterator<Item = (K, V)>>(iter: T) -> Self {
-        let list = Self::new();
+        let list = Self::with_comparator(C::default());
         for (k, v) in iter {
             list.insert(k, v);
         }
@@ -70,14 +270,14 @@ pub struct IntoIter<K, V> {
 
 impl<K, V> IntoIter<K, V>
 where
-    K: Ord + Send + Sync,
+    K: Send + Sync,
     V: Send + Sync,
 {
-    pub fn from_list<'a>(mut list: SkipList<'a, K, V>) -> Self {
+    pub fn from_list<C>(mut list: SkipList<K, V, C>) -> Self {
         unsafe {
             let next = list.head.as_ref().levels[0].load_ptr();
             for level in list.head.as_mut().levels.pointers.iter_mut() {
-                level.store_ptr(core::ptr::null_mut());
+                level.pointer.store_ptr(core::ptr::null_mut());
             }
 
             IntoIter { next }
@@ -87,7 +287,7 @@ where
 
 impl<K, V> core::iter::Iterator for IntoIter<K, V>
 where
-    K: Ord + Send + Sync,
+    K: Send + Sync,
     V: Send + Sync,
 {
     type Item = (K, V);
@@ -98,7 +298,7 @@ where
 
         let next = self.next;
 
-        self.next = unsafe { (*next).levels[0].load_ptr() };
+        self.next = unsafe { (&(*next).levels)[0].load_ptr() };
 
         let (key, val) = unsafe { (core::ptr::read(&(*next).key), core::ptr::read(&(*next).val)) };
 
@@ -1,6 +1,7 @@
 use core::borrow::Borrow;
 use core::fmt::Debug;
 use core::marker::Sync;
+use core::ops::{Bound, RangeBounds};
 use core::ptr::NonNull;
 use core::sync::atomic::Ordering;
 
@@ -11,29 +12,38 @@ use haphazard::{
     Domain
 };
 
+use crate::comparator::Comparator;
 use crate::internal::utils::{
     skiplist_basics, 
     GeneratesHeight, 
+    Head, 
     Node, 
     HEIGHT
 };
 
 pub(crate) mod tagged;
+pub mod cursor;
+pub mod entry;
 pub mod iter;
-pub use iter::{ Iter, IntoIter };
+pub mod versioned;
+pub use cursor::Cursor;
+pub use entry::{ MapEntry, VacantEntry };
+pub use iter::{ ExtractIf, GetAll, Iter, IntoIter, Range };
+pub use versioned::{ Snapshot, SnapshotIter, VersionedEntry, VersionedSkipList };
 
 skiplist_basics!(SkipList);
 
-impl<'a, K, V> Debug for SkipList<'a, K, V> {
+impl<K, V, C> Debug for SkipList<K, V, C> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("SkipList").field("head", &self.head.as_ptr()).finish()
     }
 }
 
-impl<'domain, K, V> SkipList<'domain, K, V>
+impl<K, V, C> SkipList<K, V, C>
 where
-    K: Ord + Send + Sync,
+    K: Send + Sync,
     V: Send + Sync,
+    C: Comparator<K>,
 {
     /// Inserts a value in the list given a key.
     pub fn insert<'a>(&'a self, key: K, val: V) -> Option<Entry<'a, K, V>> {
@@ -42,17 +52,34 @@ where
         let mut insertion_point = self.find(&key, false);
         let mut existing = None;
 
+        // Should another thread remove or replace the node first, we try again with whatever
+        // took its place.
         while let Some(target) = insertion_point.target.take() {
             if target.try_remove_and_tag().is_ok() {
                 unsafe {
                     let _ = self.unlink(&target, target.height(), &insertion_point.prev);
                 }
-                insertion_point = self.find(&key, false);
                 existing = Some(target);
             }
+            insertion_point = self.find(&key, false);
         };
-        
-        let mut prev = insertion_point.prev;
+
+        self.link_new(key, val, insertion_point)
+            .or(existing)
+            .map(|existing| self.to_entry(existing))
+    }
+
+    /// Links a new [Node](Node) for `key` and `val`, using the `prev` nodes of `search` as a
+    /// starting point. Should other nodes of the same key get in the way, they are replaced and
+    /// the last one of them is returned.
+    fn link_new<'a>(
+        &'a self,
+        key: K,
+        val: V,
+        search: SearchResult<'a, K, V>,
+    ) -> Option<NodeRef<'a, K, V>> {
+        let mut prev = search.prev;
+        let mut existing = None;
 
         let new_node_raw = Node::new_rand_height(key, val, self);
 
@@ -80,16 +107,285 @@ where
 
                     if target.try_remove_and_tag().is_ok() {
                         let _ = self.unlink(&target, target.height(), &search.prev);
-                        search = self.find(&new_node.key, false);
                         existing = Some(target);
                     }
+                    search = self.find(&new_node.key, false);
                 };
 
                 (starting_height, prev) = (starting, search.prev);
             }
         }
 
-        existing.map(|existing| existing.into())
+        existing
+    }
+
+    /// Returns the [Entry](Entry) for `key`, inserting `val` if the key is not present yet.
+    ///
+    /// Unlike [insert](SkipList::insert), an existing value is never replaced. If several threads
+    /// race to insert the same key, exactly one of them succeeds and all of them receive the
+    /// [Entry](Entry) of the winning value.
+    pub fn get_or_insert<'a>(&'a self, key: K, val: V) -> Entry<'a, K, V> {
+        self.get_or_insert_with(key, || val)
+    }
+
+    /// Returns the [Entry](Entry) for `key`, inserting the value returned by `f` if the key is not
+    /// present yet.
+    ///
+    /// `f` is only called if no live entry for `key` was found. Should another thread win the
+    /// race to insert `key` after `f` was called, its value is returned and ours is dropped.
+    pub fn get_or_insert_with<'a, F>(&'a self, key: K, f: F) -> Entry<'a, K, V>
+    where
+        F: FnOnce() -> V,
+    {
+        match self.try_insert_with(key, f) {
+            Ok(entry) | Err(entry) => entry,
+        }
+    }
+
+    /// Inserts `val` for `key`, unless the key is present already. Returns the [Entry](Entry) of
+    /// our value if it was inserted, or that of the existing value otherwise, in which case `val`
+    /// is dropped.
+    pub fn try_insert<'a>(&'a self, key: K, val: V) -> Result<Entry<'a, K, V>, Entry<'a, K, V>> {
+        self.try_insert_with(key, || val)
+    }
+
+    /// Like [try_insert](SkipList::try_insert), but only calls `f` if no live entry for `key` was
+    /// found.
+    fn try_insert_with<'a, F>(&'a self, key: K, f: F) -> Result<Entry<'a, K, V>, Entry<'a, K, V>>
+    where
+        F: FnOnce() -> V,
+    {
+        let mut search = self.find(&key, false);
+
+        if let Some(target) = search.target.take() {
+            return Err(self.to_entry(target));
+        }
+
+        let new_node = NodeRef::from_raw(Node::new_rand_height(key, f(), self));
+
+        // Linking the base level is the point at which our node becomes visible to others. Should
+        // another node with the same key be linked first, we back off and return that one instead.
+        loop {
+            let (prev, next) = &search.prev[0];
+            let next_ptr = next.as_ref().map_or(core::ptr::null_mut(), |n| n.as_ptr());
+
+            new_node.levels[0].store_ptr(next_ptr);
+            new_node.add_ref();
+
+            if prev.levels[0].compare_exchange(next_ptr, new_node.as_ptr()).is_ok() {
+                break;
+            }
+
+            new_node.sub_ref();
+            search = self.find(&new_node.key, false);
+
+            if let Some(target) = search.target.take() {
+                // # Safety
+                //
+                // 1. `new_node` was never linked, so no other thread can have observed it.
+                unsafe { Node::drop(new_node.as_ptr()) };
+
+                return Err(self.to_entry(target));
+            }
+        }
+
+        self.state.len.fetch_add(1, Ordering::AcqRel);
+
+        let (mut starting_height, mut prev) = (1, search.prev);
+
+        unsafe {
+            while let Err(starting) = self.link_nodes(&new_node, prev, starting_height) {
+                (starting_height, prev) = (starting, self.find(&new_node.key, false).prev);
+            }
+        }
+
+        Ok(self.to_entry(new_node))
+    }
+
+    /// Inserts `val` for `key` without replacing any existing values, which allows the list to be
+    /// used as a multimap. Values of the same key are kept in insertion order.
+    ///
+    /// Duplicates are linked at full height behind all values of the same key, so they do not slow
+    /// down lookups.
+    pub fn insert_multi<'a>(&'a self, key: K, val: V) -> Entry<'a, K, V> {
+        let new_node = NodeRef::from_raw(Node::new_rand_height(key, val, self));
+
+        // We link behind all nodes of the same key.
+        let search = || self.find_by(|k| self.compare(k, &new_node.key).is_le());
+
+        let (mut starting_height, mut prev) = (0, search().prev);
+
+        self.state.len.fetch_add(1, Ordering::AcqRel);
+
+        unsafe {
+            while let Err(starting) = self.link_nodes(&new_node, prev, starting_height) {
+                (starting_height, prev) = (starting, search().prev);
+            }
+        }
+
+        self.to_entry(new_node)
+    }
+
+    /// Builds a list from an iterator whose keys are sorted in ascending order and contain no
+    /// duplicates. Rather than searching for each insertion point, the towers are linked from left
+    /// to right in a single pass, which takes O(n).
+    ///
+    /// In debug builds, this panics if the keys are not strictly ascending.
+    pub fn from_sorted_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = (K, V)>,
+        C: Default,
+    {
+        let list = Self::with_comparator(C::default());
+
+        // The last node linked on each level.
+        let mut last = [list.head.as_ptr().cast::<Node<K, V>>(); HEIGHT];
+        let mut len = 0;
+
+        // # Safety
+        //
+        // No other thread can observe the list while it is built.
+        unsafe {
+            for (key, val) in iter {
+                debug_assert!(
+                    len == 0 || list.compare(&(*last[0]).key, &key).is_lt(),
+                    "keys must be sorted and unique"
+                );
+
+                let new_node = Node::new_rand_height(key, val, &list);
+                len += 1;
+
+                for (i, last) in last.iter_mut().enumerate().take((*new_node).height()) {
+                    (&(**last).levels)[i].store_ptr(new_node);
+                    (*new_node).add_ref();
+
+                    *last = new_node;
+                }
+            }
+        }
+
+        list.state.len.store(len, Ordering::Relaxed);
+
+        list
+    }
+
+    /// Splits the list in two at `key`. The entries whose keys are greater than or equal to `key`
+    /// are moved to the returned list.
+    ///
+    /// The towers are cut at every level at the boundary, without reinserting any nodes. Unlike
+    /// the single-threaded list, we do not keep track of positions, so the entries moved to the
+    /// new list have to be counted to fix up both lengths. This makes `split_off` O(n) in the
+    /// number of moved entries, on top of the O(log n) boundary search.
+    pub fn split_off<Q>(&mut self, key: &Q) -> Self
+    where
+        K: Borrow<Q>,
+        Q: ?Sized,
+        C: Comparator<Q> + Clone,
+    {
+        let other = Self::with_comparator(self.comparator.clone());
+        let search = self.find_by(|k| self.compare(k, key).is_lt());
+
+        // # Safety
+        //
+        // We have an exclusive reference to both lists, so no other thread can observe them while
+        // their towers are re-stitched.
+        unsafe {
+            let other_head = other.head.as_ref();
+
+            for (i, (prev, _)) in search.prev.iter().enumerate() {
+                other_head.levels[i].store_ptr(prev.levels[i].load_ptr());
+                prev.levels[i].store_ptr(core::ptr::null_mut());
+            }
+
+            let mut moved = 0;
+            let mut node = other_head.levels[0].load_ptr();
+
+            while !node.is_null() {
+                if !(*node).removed() {
+                    moved += 1;
+                }
+
+                node = (&(*node).levels)[0].load_ptr();
+            }
+
+            other.state.len.store(moved, Ordering::Relaxed);
+            other.state.max_height.store(self.state.max_height.load(Ordering::Relaxed), Ordering::Relaxed);
+            self.state.len.fetch_sub(moved, Ordering::Relaxed);
+        }
+
+        other
+    }
+
+    /// Moves all entries of `other` into the list, leaving `other` empty.
+    ///
+    /// If no key of `other` is less than those of the list, the towers of `other` are linked
+    /// behind ours at every level. Otherwise, the entries of `other` are inserted one by one.
+    /// Either way, keys present in both lists keep all of their entries, with those of `other`
+    /// following ours.
+    pub fn append(&mut self, other: &mut Self) {
+        if other.is_empty() {
+            return;
+        }
+
+        let ordered = match (self.get_last(), other.get_first()) {
+            (Some(last), Some(first)) => self.compare(last.key(), first.key()).is_le(),
+            _ => true,
+        };
+
+        // # Safety
+        //
+        // We have an exclusive reference to both lists, so no other thread can observe them while
+        // their towers are re-stitched.
+        unsafe {
+            let other_head = other.head.as_ref();
+
+            if !ordered {
+                let mut node = other_head.levels[0].load_ptr();
+
+                for i in 0..HEIGHT {
+                    other_head.levels[i].store_ptr(core::ptr::null_mut());
+                }
+                other.state.len.store(0, Ordering::Relaxed);
+
+                while !node.is_null() {
+                    let next = (&(*node).levels)[0].load_ptr();
+
+                    // Removed nodes that are still linked are dropped, just like the list would.
+                    if (*node).removed() {
+                        Node::drop(node);
+                    } else {
+                        let key = core::ptr::read(&(*node).key);
+                        let val = core::ptr::read(&(*node).val);
+                        Node::dealloc(node);
+
+                        self.insert_multi(key, val);
+                    }
+
+                    node = next;
+                }
+
+                return;
+            }
+
+            let search = self.find_by(|_| true);
+
+            for (i, (prev, _)) in search.prev.iter().enumerate() {
+                prev.levels[i].store_ptr(other_head.levels[i].load_ptr());
+                other_head.levels[i].store_ptr(core::ptr::null_mut());
+            }
+
+            self.state.len.fetch_add(other.len(), Ordering::Relaxed);
+            self.state.max_height.fetch_max(other.state.max_height.load(Ordering::Relaxed), Ordering::Relaxed);
+            other.state.len.store(0, Ordering::Relaxed);
+        }
+    }
+
+    /// Gets the [MapEntry](MapEntry) for `key`, which is either occupied by a live value or vacant.
+    pub fn entry<'a>(&'a self, key: K) -> MapEntry<'a, K, V, C> {
+        match self.find(&key, false).target {
+            Some(target) => MapEntry::Occupied(self.to_entry(target)),
+            None => MapEntry::Vacant(VacantEntry::new(self, key)),
+        }
     }
 
     /// This function is unsafe, as it does not check whether new_node or link node are valid
@@ -112,14 +408,23 @@ where
 
             let curr_next = new_node.levels[i].load_ptr();
 
-            if new_node.removed() {
+            if new_node.removed() || new_node.levels[0].load_tag() != 0 {
+                break;
+            }
+
+            // Duplicates are linked behind all values of their key at every level. A duplicate
+            // inserted behind us concurrently may already be linked at this level, in which case
+            // we stop building, so that the levels keep the order of the base level.
+            if i > 0 && !self.is_head(prev.as_ptr()) && self.compare(&prev.key, &new_node.key).is_eq()
+                && self.follows(new_node, prev)
+            {
                 break;
             }
 
             // We check if the next node is actually lower in key than our current node.
             // If the key is not greater we stop building our node.
             if next.as_ref()
-                .and_then(|n| if n.key <= new_node.key && !new_node.removed() {
+                .and_then(|n| if self.compare(&n.key, &new_node.key).is_le() && !new_node.removed() {
                     Some(())
                 } else {
                     None
@@ -158,899 +463,2758 @@ where
 
         // IF we linked the node, yet it was removed during that process, there may be some levels
         // that we linked and that were missed by the removers. We search to unlink those too.
-        if new_node.removed() {
-            self.find(&new_node.key, false);
+        if new_node.removed() || new_node.levels[0].load_tag() != 0 {
+            self.find_by(|k| self.compare(k, &new_node.key).is_le());
         }
 
         Ok(())
     }
 
-    #[allow(unused_assignments)]
-    pub fn remove<'a>(&'a self, key: &K) -> Option<Entry<'a, K, V>>
-    where
-        K: Send,
-        V: Send,
-    {
-    match self.find(key, false) {
-        SearchResult {
-                target: Some(target),
-                prev,
-            } => {
-
-                // Set the target state to being removed
-                // If this errors, it is already being removed by someone else
-                // and thus we exit early.
-                if target.set_removed().is_err() {
-                    return None;
-                }
-
-                // # Safety:
-                // 1. `key` and `val` will not be tempered with.
-                // TODO This works for now, yet once `Atomic` is used
-                // this may need to change.
-                let height = target.height();
-
-                if let Err(_) = target.tag_levels(1) {
-                    panic!("SHOULD NOT BE TAGGED!")
-                };
+    /// Checks whether `other`, a node of the same key as `node`, follows it on the base level.
+    /// Should the base level change under us, we err on the side of `other` following `node`.
+    fn follows<'a>(&'a self, node: &NodeRef<'a, K, V>, other: &NodeRef<'a, K, V>) -> bool {
+        let mut next = NodeRef::from_untagged(&node.levels[0]);
 
-                // #Safety:
-                // 1. The height we got from the `node` guarantees it is a valid height for levels.
-                unsafe {
-                    if self.unlink(&target, height, &prev).is_err() {
-                        self.find(&key, false);
+        loop {
+            match next {
+                Ok(Some(n)) if self.compare(&n.key, &node.key).is_eq() => {
+                    if core::ptr::eq(n.as_ptr(), other.as_ptr()) {
+                        return true;
                     }
+
+                    next = NodeRef::from_untagged(&n.levels[0]);
                 }
+                Ok(_) => return false,
+                Err(()) => return true,
+            }
+        }
+    }
 
+    pub fn remove<'a, Q>(&'a self, key: &Q) -> Option<Entry<'a, K, V>>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized,
+        C: Comparator<Q>,
+    {
+        loop {
+            let search = self.find(key, false);
+
+            // Set the target state to being removed
+            // If this errors, it is already being removed by someone else
+            // and thus we exit early.
+            if search.target.as_ref()?.set_removed().is_err() {
+                return None;
+            }
 
-                Some(target.into())
+            // Should the node have been replaced before we tagged it, we remove its replacement.
+            if let Some(removed) = self.unlink_removed(search) {
+                return Some(removed);
             }
-            _ => None,
         }
     }
 
-    /// Logically removes the node from the list by linking its adjacent nodes to one-another.
+    /// Removes `key` and hands back the key and value by value.
     ///
-    /// # Safety
-    /// 1. All indices in [0, height) are valid indices for `node.levels`.
-    unsafe fn unlink<'a>(
-        &self,
-        node: &'a NodeRef<'a, K, V>,
-        height: usize,
-        previous_nodes: &[(NodeRef<'a, K, V>, Option<NodeRef<'a, K, V>>); HEIGHT],
-    ) -> Result<(), usize> {
-        // safety check against UB caused by unlinking the head
-        if self.is_head(node.as_ptr()) {
-            panic!()
-        }
-
-        // # Safety
-        //
-        // 1.-3. Some as method and covered by method caller.
-        // 4. We are not unlinking the head. - Covered by previous safety check.
-        for (i, (prev, next)) in previous_nodes.iter().enumerate().take(height).rev() {
-            let (new_next, _tag) = node.levels[i].load_decomposed();
-            let _next_ptr = next.as_ref().map_or(core::ptr::null_mut(), |n| n.as_ptr());
-
-            // We check if the previous node is being removed after we have already unlinked
-            // from it as the prev nodes expects us to do this.
-            // We still need to stop the unlink here, as we will have to relink to the actual,
-            // lively previous node at this level as well.
+    /// This never waits for other threads: should another thread still hold an [Entry] of the
+    /// removed node, it is returned as `Err` instead, like [remove](SkipList::remove) does, and the
+    /// node is freed once the last [Entry] of it is dropped.
+    ///
+    /// Whether anyone holds the node is only known to the hazard pointer domain. Should another
+    /// thread be reclaiming garbage at the same moment, we may not learn in time that nobody
+    /// does, and also hand back an `Err`.
+    pub fn remove_owned<'a, Q>(&'a self, key: &Q) -> Option<Result<(K, V), Entry<'a, K, V>>>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized,
+        C: Comparator<Q>,
+    {
+        loop {
+            let search = self.find(key, false);
+            let target = search.target.clone()?;
 
-            // Performs a compare_exchange, expecting the old value of the pointer to be the current
-            // node. If it is not, we cannot make any reasonable progress, so we search again.
-            if let Err((_other, _tag)) = prev.levels[i].compare_exchange(
-                node.as_ptr(),
-                new_next,
-            ) {
-                return Err(i + 1);
+            if target.set_removed().is_err() {
+                return None;
             }
 
-            if self.sub_ref(&node).is_none() {
-                break;
+            // Our own reference keeps the node from being retired once it has been unlinked.
+            target.add_ref();
+
+            let Some(entry) = self.unlink_removed(search) else {
+                // The node was replaced before we tagged it, so we remove its replacement.
+                self.sub_ref(&target);
+                continue;
             };
-        }
 
-        self.state.len.fetch_sub(1, Ordering::AcqRel);
+            drop(target);
 
-        // we see if we can drop some pointers in the list.
-        self.garbage.domain.eager_reclaim();
-        Ok(())
+            // # Safety
+            // 1. We removed the node and took a reference before unlinking it.
+            return unsafe { Some(self.take_removed(entry)) };
+        }
     }
 
-    /// Decrements the reference count of the `Node` by 1. If the reference count is thus 0, we
-    /// retire the node.
-    fn sub_ref<'a>(&self, node: &NodeRef<'a, K, V>) -> Option<()> {
-        if node.try_sub_ref().expect("to not overflow") == 0 {
-            self.retire_node(node.as_ptr());
-            None
-        } else {
-            Some(())
+    /// Removes the oldest value of `key`. See [insert_multi](SkipList::insert_multi).
+    ///
+    /// This is the same as [remove](SkipList::remove).
+    pub fn remove_one<'a, Q>(&'a self, key: &Q) -> Option<Entry<'a, K, V>>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized,
+        C: Comparator<Q>,
+    {
+        self.remove(key)
+    }
+
+    /// Removes all values of `key` and returns how many were removed.
+    pub fn remove_all<Q>(&self, key: &Q) -> usize
+    where
+        K: Borrow<Q>,
+        Q: ?Sized,
+        C: Comparator<Q>,
+    {
+        let mut removed = 0;
+
+        // Unlike `remove`, `remove_if` only gives up once no live value is left.
+        while self.remove_if(key, |_| true).is_some() {
+            removed += 1;
         }
+
+        removed
     }
 
-    /// Unlink [Node](Node) `curr` at the given level of [Node](Node) `prev` by exchanging the pointer for `next`.
+    /// Removes all entries for which `pred` returns `false`.
     ///
-    /// # Safety
+    /// See [extract_if](SkipList::extract_if), which this is built on.
+    pub fn retain<F>(&self, mut pred: F)
+    where
+        F: FnMut(&K, &V) -> bool,
+    {
+        self.extract_if(|k, v| !pred(k, v)).for_each(drop);
+    }
+
+    /// Returns an iterator that removes and yields all entries for which `pred` returns `true`.
     ///
-    /// 1. `prev`, `curr`, are protected accesses.
-    #[allow(unused)]
-    unsafe fn unlink_level<'a>(
-        &'a self,
-        prev: &NodeRef<'a, K, V>,
-        curr: NodeRef<'a, K, V>,
-        next: Option<NodeRef<'a, K, V>>,
-        level: usize,
-    ) -> Result<Option<NodeRef<'a, K, V>>, ()> {
-        // The pointer to `next` is tagged to signal unlinking. 
-        let next_ptr = next.as_ref().map_or(core::ptr::null_mut(), |n| n.as_ptr());
+    /// The list is traversed once along its base level. Matching nodes are only marked as removed
+    /// on the way, and are unlinked in bulk once the iterator is exhausted or dropped. Entries
+    /// inserted or removed by other threads during the traversal may or may not be visited.
+    pub fn extract_if<'a, F>(&'a self, pred: F) -> ExtractIf<'a, K, V, F, C>
+    where
+        F: FnMut(&K, &V) -> bool,
+    {
+        ExtractIf::new(self, pred)
+    }
 
-        if let Ok(_) = prev.levels[level].compare_exchange(curr.as_ptr(), next_ptr) {
-            self.sub_ref(&curr);
+    /// Marks the [Node](Node) behind `entry` as removed and tags its levels, without unlinking it.
+    /// Returns `false` if it has been removed already.
+    fn mark_removed<'a>(&'a self, entry: &Entry<'a, K, V>) -> bool {
+        if entry.set_removed().is_err() {
+            return false;
+        }
 
-            Ok(next)
-        } else {
-            Err(())
+        // The node was replaced before we tagged it.
+        if entry.tag_levels(1).is_err() {
+            return false;
         }
-    }
 
-    fn retire_node(&self, node_ptr: *mut Node<K, V>) {
-        unsafe {
-            self.garbage
-                .domain
-                .retire_ptr::<Node<K, V>, DeallocOnDrop<K, V>>(node_ptr)
-        };
-    }
+        // The node is no longer part of the list, even though it is only unlinked later.
+        self.state.len.fetch_sub(1, Ordering::AcqRel);
 
-    fn find<'a>(&'a self, key: &K, search_closest: bool) -> SearchResult<'a, K, V> {
-        let head = unsafe { &(*self.head.as_ptr()) };
+        true
+    }
 
-        // Initialize the `prev` array.
-        let mut prev = unsafe {
-            let mut prev: [core::mem::MaybeUninit<(NodeRef<'a, K, V>, Option<NodeRef<'a, K, V>>)>; HEIGHT] 
-                = core::mem::MaybeUninit::uninit().assume_init();
+    /// Removes the first live [Node](Node) of the list. Should another thread remove it first,
+    /// we move on to the next one, so this only returns `None` once the list is empty.
+    pub fn remove_first<'a>(&'a self) -> Option<Entry<'a, K, V>> {
+        loop {
+            let first = self.get_first()?;
 
-            for (i, level) in prev.iter_mut().enumerate() {
-                core::ptr::write(
-                    level.as_mut_ptr(), 
-                    (NodeRef::from_raw(self.head.cast::<Node<K, V>>().as_ptr()), NodeRef::from_maybe_tagged(&self.head.as_ref().levels[i]))
-                )
+            if let Some(removed) = self.remove_entry(&first) {
+                return Some(removed);
             }
+        }
+    }
 
-            core::mem::transmute::<_, [(NodeRef<'a, K, V>, Option<NodeRef<'a, K, V>>); HEIGHT]>(prev)
-        };
-
+    /// Removes the last live [Node](Node) of the list. Should another thread remove it first, we
+    /// move on to the new last one, so this only returns `None` once the list is empty.
+    pub fn remove_last<'a>(&'a self) -> Option<Entry<'a, K, V>> {
+        loop {
+            let last = self.get_last()?;
 
-        '_search: loop {
-            let mut level = self.state.max_height.load(Ordering::Relaxed);
-            // Find the first and highest node tower
-            while level > 1 && head.levels[level - 1].load_ptr().is_null() {
-                level -= 1;
+            if let Some(removed) = self.remove_entry(&last) {
+                return Some(removed);
             }
+        }
+    }
 
-            // We need not protect the head, as it will always be valid, as long as we are in a sane
-            // state.
-            let mut curr = NodeRef::from_raw(self.head.as_ptr().cast::<Node<K, V>>());
+    /// Like [remove_first](SkipList::remove_first), but hands back the key and value by value.
+    /// See [remove_owned](SkipList::remove_owned) for when the [Entry] is returned instead.
+    pub fn remove_first_owned<'a>(&'a self) -> Option<Result<(K, V), Entry<'a, K, V>>> {
+        self.remove_owned_by(|| self.get_first())
+    }
 
-            // steps:
-            // 1. Go through each level until we reach a node with a key GEQ to ours or that is null
-            //     1.1 If we are equal, then the node must either be marked as removed or removed nodes
-            //       are allowed in this search.
-            //       Should this be the case, then we drop down a level while also protecting a pointer
-            //       to the current node, in order to keep the `Level` valid in our `prev` array.
-            //     1.2 If we the `next` node is less or equal but removed and removed nodes are
-            //       disallowed, then we set our current node to the next node.
-            while level > 0 {
-                let next = unsafe {
-                    let mut next = NodeRef::from_maybe_tagged(&curr.levels[level - 1]);
-                    loop {
-                        if next.is_none() {
-                            break next;
-                        }
+    /// Like [remove_last](SkipList::remove_last), but hands back the key and value by value.
+    /// See [remove_owned](SkipList::remove_owned) for when the [Entry] is returned instead.
+    pub fn remove_last_owned<'a>(&'a self) -> Option<Result<(K, V), Entry<'a, K, V>>> {
+        self.remove_owned_by(|| self.get_last())
+    }
 
-                        if let Some(n) = next.as_ref() {
-                            if n.levels[level - 1].load_tag() == 0 {
-                                break next;
-                            }
-                        }
+    /// Removes the [Node](Node) returned by `get` and takes it out of the list by value, trying
+    /// again with the next one `get` returns should another thread remove it first.
+    fn remove_owned_by<'a, F>(&'a self, get: F) -> Option<Result<(K, V), Entry<'a, K, V>>>
+    where
+        F: Fn() -> Option<Entry<'a, K, V>>,
+    {
+        loop {
+            let entry = get()?;
 
-                        let n = next.unwrap();
+            if entry.set_removed().is_err() {
+                continue;
+            }
 
-                        let new_next = NodeRef::from_maybe_tagged(&n.levels[level - 1]);
+            // Our own reference keeps the node from being retired once it has been unlinked.
+            entry.add_ref();
 
-                        let Ok(n) = self.unlink_level(&curr, n, new_next, level - 1) else {
-                            continue '_search;
-                        };
+            let node = &entry.node;
+            let SearchResult { prev, .. } = self.find_by(|k| self.compare(k, &node.key).is_lt());
 
-                        next = n
+            let search = SearchResult { prev, target: Some(node.clone()) };
 
-                    }
-                };
+            let Some(removed) = self.unlink_removed(search) else {
+                // The node was replaced before we tagged it, so we move on to its replacement.
+                self.sub_ref(node);
+                continue;
+            };
 
-                match next {
-                    Some(next) 
-                        // This check should ensure that we always get a non-removed node, if there
-                        // is one, of our target key, as long as allow removed is set to false.
-                        if (*next).key < *key => {
+            drop(entry);
 
-                        // If the current node is being removed, we try to help unlinking it at this level.
-                        // Update previous_nodes.
-                        prev[level - 1] = (curr, Some(next.clone()));
+            // # Safety
+            // 1. We removed the node and took a reference before unlinking it.
+            return unsafe { Some(self.take_removed(removed)) };
+        }
+    }
 
-                        curr = next;
-                    },
-                    next => {
-                        // Update previous_nodes.
-                        prev[level - 1] = (curr.clone(), next);
+    /// Removes the [Node](Node) behind `entry`, unless it has been removed already.
+    fn remove_entry<'a>(&'a self, entry: &Entry<'a, K, V>) -> Option<Entry<'a, K, V>> {
+        let node = &entry.node;
 
-                        level -= 1;
-                    }
-                }
-            }
+        if node.set_removed().is_err() {
+            return None;
+        }
 
-            unsafe {
-                return if search_closest {
-                    let mut next = NodeRef::from_maybe_tagged(&curr.levels[level - 1]);
-                    loop {
-                        if next.is_none() {
-                            break;
-                        }
+        let SearchResult { prev, .. } = self.find_by(|k| self.compare(k, &node.key).is_lt());
 
-                        if let Some(n) = next.as_ref() {
-                            if n.levels[level - 1].load_tag() == 0 {
-                                break;
-                            }
-                        }
+        self.unlink_removed(SearchResult { prev, target: Some(node.clone()) })
+    }
 
-                        let n = next.unwrap();
+    /// Removes the live [Node](Node) for `key`, but only if `pred` holds for its value.
+    ///
+    /// `pred` is evaluated on the node that ends up being removed. If another thread removes or
+    /// replaces that node first, the search is repeated and `pred` is evaluated again.
+    pub fn remove_if<'a, Q, F>(&'a self, key: &Q, mut pred: F) -> Option<Entry<'a, K, V>>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized,
+        C: Comparator<Q>,
+        F: FnMut(&V) -> bool,
+    {
+        loop {
+            let search = self.find(key, false);
+            let target = search.target.as_ref()?;
 
-                        let new_next = NodeRef::from_maybe_tagged(&n.levels[level - 1]);
+            if !pred(&target.val) {
+                return None;
+            }
 
-                        let Ok(n) = self.unlink_level(&curr, n, new_next, level - 1) else {
-                            continue '_search;
-                        };
+            // Whoever marks the node as removed owns its removal, so `pred` was evaluated on the
+            // node we are removing. Otherwise we help the owner, so that we find the next node.
+            if target.set_removed().is_err() {
+                let _ = target.tag_levels(1);
+            } else if let Some(removed) = self.unlink_removed(search) {
+                return Some(removed);
+            }
+        }
+    }
 
-                        next = n
-                    }
+    /// Replaces the value of the live [Node](Node) for `key` with `val`, but only if `pred` holds
+    /// for the current value. On success, the [Entry](Entry) of the replaced value is returned,
+    /// otherwise `val` is handed back.
+    ///
+    /// The new value takes the place of the old one at once, so other threads never find the key
+    /// missing. As with [remove_if](SkipList::remove_if), `pred` is evaluated again if the node is
+    /// removed or replaced by another thread in the meantime.
+    pub fn replace_if<'a, F>(
+        &'a self,
+        mut key: K,
+        mut pred: F,
+        mut val: V,
+    ) -> Result<Entry<'a, K, V>, V>
+    where
+        F: FnMut(&V) -> bool,
+    {
+        loop {
+            let search = self.find(&key, false);
 
-                    SearchResult { prev, target: next }
-                } else {
-                    match NodeRef::from_maybe_tagged(&prev[0].0.as_ref().levels[0]) {
-                        Some(next) if next.key == *key && !next.removed() => SearchResult { prev, target: Some(next) },
-                        _ => SearchResult { prev, target: None }
-                    }
-                }
+            let Some(target) = search.target else {
+                return Err(val);
+            };
+
+            if !pred(&target.val) {
+                return Err(val);
+            }
+
+            match self.replace_node(&target, key, val) {
+                Ok(()) => return Ok(self.to_entry(target)),
+                Err((k, v)) => (key, val) = (k, v),
             }
         }
     }
 
-    pub fn get<'a>(&'a self, key: &K) -> Option<Entry<'a, K, V>> {
-        if self.is_empty() {
-            return None;
+    /// Links a new [Node](Node) for `key` and `val` right behind `target`, and tags the base
+    /// level of `target` in the same exchange, so that the new node takes its place at once.
+    ///
+    /// Whoever tags the base level of a node first decides whether it is removed or replaced, so
+    /// should `target` be removed or replaced by someone else first, `key` and `val` are handed
+    /// back.
+    fn replace_node<'a>(
+        &'a self,
+        target: &NodeRef<'a, K, V>,
+        key: K,
+        val: V,
+    ) -> Result<(), (K, V)> {
+        let new_node = NodeRef::from_raw(Node::new_rand_height(key, val, self));
+
+        // The reference of the base level, which we link below.
+        new_node.add_ref();
+
+        loop {
+            let (next, tag) = target.levels[0].load_decomposed();
+
+            if tag != 0 {
+                // # Safety
+                //
+                // 1. `new_node` was never linked, so no other thread can have observed it.
+                unsafe {
+                    let key = core::ptr::read(&new_node.key);
+                    let val = core::ptr::read(&new_node.val);
+                    Node::dealloc(new_node.as_ptr());
+
+                    return Err((key, val));
+                }
+            }
+
+            new_node.levels[0].store_ptr(next);
+
+            if target.levels[0]
+                .compare_exchange_with_tag(next, 0, new_node.as_ptr(), 2)
+                .is_ok()
+            {
+                break;
+            }
         }
 
-        // Perform safety check for whether we are dealing with the head.
-        match self.find(key, false) {
-            SearchResult {
-                target: Some(target),
-                ..
-            } => Some(Entry::from(target)),
-            _ => None,
+        // `target` is no longer part of the list, so we finish its removal. As `new_node` took
+        // its place, the length of the list does not change.
+        let _ = target.set_removed();
+        let _ = target.tag_levels(1);
+
+        // Searching for the upper levels of `new_node` unlinks `target` on the way.
+        let search = || self.find_by(|k| self.compare(k, &new_node.key).is_lt());
+
+        let (mut starting_height, mut prev) = (1, search().prev);
+
+        unsafe {
+            while let Err(starting) = self.link_nodes(&new_node, prev, starting_height) {
+                (starting_height, prev) = (starting, search().prev);
+            }
         }
-    }
 
-    fn is_head(&self, ptr: *const Node<K, V>) -> bool {
-        std::ptr::eq(ptr, self.head.as_ptr().cast())
+        Ok(())
     }
 
-    fn next_node<'a>(&'a self, node: &Entry<'a, K, V>) -> Option<Entry<'a, K, V>> {
-        let node: &NodeRef<'_, _, _> = unsafe { core::mem::transmute(node) };
+    /// Replaces the value for `key` with `new`, but only if the current value equals `expected`.
+    /// On failure `new` is handed back.
+    pub fn compare_and_swap<'a>(
+        &'a self,
+        key: K,
+        expected: &V,
+        new: V,
+    ) -> Result<Entry<'a, K, V>, V>
+    where
+        V: PartialEq,
+    {
+        self.replace_if(key, |val| val == expected, new)
+    }
 
-        // This means we have a stale node and cannot return a sane answer!
-        if node.levels[0].load_tag() == 1 {
-            return self.find(&node.key, true).target.map(|t| t.into())
+    /// Tags and unlinks the target of `search`, which has to have been marked as removed by the
+    /// caller. Returns `None` if the target was replaced before we could tag it.
+    fn unlink_removed<'a>(&'a self, search: SearchResult<'a, K, V>) -> Option<Entry<'a, K, V>> {
+        let SearchResult { target: Some(target), prev } = search else {
+            return None;
         };
 
-        let mut next = NodeRef::from_maybe_tagged(&node.levels[0])?;
-        
-        // Unlink and skip all removed `Node`s we may encounter.
-        while next.levels[0].load_tag() == 1 {
-            let new = NodeRef::from_maybe_tagged(&next.levels[0]);
-            next = unsafe {
-                self.unlink_level(&node, next, new, 0)
-                    .ok()
-                    .unwrap_or_else(|| self.find(&node.key, true).target)?
-            };
+        // # Safety:
+        // 1. `key` and `val` will not be tempered with.
+        // TODO This works for now, yet once `Atomic` is used
+        // this may need to change.
+        let height = target.height();
+
+        target.tag_levels(1).ok()?;
+
+        // #Safety:
+        // 1. The height we got from the `node` guarantees it is a valid height for levels.
+        unsafe {
+            // Searching past all nodes of the key helps unlink `target` even if it is preceded
+            // by duplicates.
+            if self.unlink(&target, height, &prev).is_err() {
+                self.find_by(|k| self.compare(k, &target.key).is_le());
+            }
         }
 
-        Some(next.into())
+        Some(self.to_entry(target))
     }
 
-    pub fn get_first<'a>(&'a self) -> Option<Entry<'a, K, V>> {
-        if self.is_empty() {
-            return None;
+    /// Moves the key and value out of `entry`, if its node is neither linked nor protected by
+    /// anyone else. Otherwise `entry` is handed back, and its node is freed once it is no longer
+    /// protected.
+    ///
+    /// # Safety
+    /// 1. The caller removed the node of `entry` and took a reference to it before unlinking it,
+    ///    so that it is not retired by whoever unlinks its last level.
+    unsafe fn take_removed<'a>(
+        &'a self,
+        entry: Entry<'a, K, V>,
+    ) -> Result<(K, V), Entry<'a, K, V>> {
+        let node_ptr = entry.node.as_ptr();
+
+        // Inserters may still be linking upper levels of the node. Searching past it unlinks
+        // those, after which only our own reference should be left.
+        self.find_by(|k| self.compare(k, &(*node_ptr).key).is_le());
+
+        if (*node_ptr).try_release().is_err() {
+            // Whoever unlinks the last level retires the node, which our entry still protects.
+            self.sub_ref(&entry.node);
+            return Err(entry);
         }
 
-        let curr = NodeRef::from_raw(self.head.as_ptr().cast::<Node<K, V>>());
+        // # Safety
+        //
+        // The node is unlinked from every level and we dropped its last reference, so nobody else
+        // retires it and no new reader can reach it. It is retired as `ReleaseOnDrop`, which does
+        // not free it, but only takes part in the handshake described there. The node thus stays
+        // valid until one of us has seen the other's reference.
+        self.garbage
+            .domain
+            .retire_ptr::<Node<K, V>, ReleaseOnDrop<K, V>>(node_ptr);
+
+        drop(entry);
+        self.garbage.domain.eager_reclaim();
 
-        self.next_node(&curr.into())
-    }
+        // # Safety
+        //
+        // `retire_ptr` asks us not to protect a retired pointer anymore, since the domain may
+        // drop it while it is protected. Dropping a `ReleaseOnDrop` does not free the node
+        // though, and the node can not be freed before we add our reference below, so it is still
+        // valid here. Should we hand the node back, this protects it against the second
+        // retirement in `ReleaseOnDrop`.
+        let node = NodeRef::from_raw_in(node_ptr, self.garbage.domain);
+
+        if (*node_ptr).add_ref() == 0 {
+            // The domain has not dropped the `ReleaseOnDrop` yet, so readers may still protect
+            // the node. Once it is dropped, it sees our reference and retires the node again.
+            return Err(self.to_entry(node));
+        }
 
-    pub fn get_last<'a>(&'a self) -> Option<Entry<'a, K, V>> {
-        let mut curr = self.get_first()?;
+        // The `ReleaseOnDrop` was dropped before we added our reference, so no hazard pointer
+        // protected the node then, and nobody could protect it since. It is ours now.
+        drop(node);
 
-        while let Some(next) = self.next_node(&curr) {
-            curr = next;
-        }
+        let key = core::ptr::read(&(*node_ptr).key);
+        let val = core::ptr::read(&(*node_ptr).val);
 
-        return Some(curr.into())
-    }
+        Node::dealloc(node_ptr);
 
-    pub fn iter<'a>(&'a self) -> Iter<'a, K, V> {
-        Iter::from_list(self)
+        Ok((key, val))
     }
-}
 
-impl<'domain, K, V> Default for SkipList<'domain, K, V>
-where
-    K: Sync,
-    V: Sync,
-{
-    fn default() -> Self {
-        Self::new()
+    /// Unlinks all nodes that have been tagged for removal, going through the list level by level
+    /// from the top. This is how [mark_removed](SkipList::mark_removed) nodes are unlinked in bulk.
+    fn unlink_all_removed(&self) {
+        let height = self.state.max_height.load(Ordering::Relaxed);
+
+        // # Safety
+        // 1. The head lives as long as the list does.
+        unsafe { self.unlink_removed_from(self.head, height) }
     }
-}
 
-unsafe impl<'domain, K, V> Send for SkipList<'domain, K, V>
-where
-    K: Send + Sync,
-    V: Send + Sync,
-{
-}
+    /// Unlinks all tagged nodes following `head` on its lowest `height` levels. Runs of
+    /// consecutive tagged nodes are unlinked with a single exchange on the node preceding them.
+    ///
+    /// # Safety
+    /// 1. `head` stays valid until this returns.
+    unsafe fn unlink_removed_from(&self, head: NonNull<Head<K, V>>, height: usize) {
+        let head = || NodeRef::from_raw(head.as_ptr().cast::<Node<K, V>>());
+
+        for level in (0..height).rev() {
+            let mut curr = head();
+
+            'level: loop {
+                // If `curr` is being removed at this level, its pointer can not be trusted.
+                let Ok(next) = NodeRef::from_untagged(&curr.levels[level]) else {
+                    curr = head();
+                    continue;
+                };
 
-unsafe impl<'domain, K, V> Sync for SkipList<'domain, K, V>
-where
-    K: Send + Sync,
-    V: Send + Sync,
-{
-}
+                let Some(first) = next else {
+                    break;
+                };
 
-// TODO Make sure this is sound!
-impl<'domain, K, V> From<super::skiplist::SkipList<'domain, K, V>> for SkipList<'domain, K, V>
-where
-    K: Sync,
-    V: Sync,
-{
-    fn from(list: super::skiplist::SkipList<'domain, K, V>) -> Self {
-        unsafe { core::mem::transmute(list) }
-    }
-}
+                if first.levels[level].load_tag() == 0 {
+                    curr = first;
+                    continue;
+                }
 
+                // Nodes are never linked behind, or unlinked from, a tagged node. So as long as
+                // `first` is still linked to `curr`, the whole run following it is linked as well,
+                // which makes the nodes we protect along the way safe to access.
+                let mut run = 1;
+                let mut after = NodeRef::from_maybe_tagged(&first.levels[level]);
 
-#[allow(dead_code)]
-pub struct Entry<'a, K: 'a, V: 'a> {
-    node: core::ptr::NonNull<Node<K, V>>,
-    _hazard: haphazard::HazardPointer<'a, Global>,
-}
+                loop {
+                    if curr.levels[level].load_decomposed() != (first.as_ptr(), 0) {
+                        curr = head();
+                        continue 'level;
+                    }
 
-impl<'a, K, V> Entry<'a, K, V> {
-    pub fn val(&self) -> &V {
-        // #Safety
-        //
-        // Our `HazardPointer` ensures that our pointers is valid.
-        unsafe { &self.node.as_ref().val }
-    }
+                    match after {
+                        Some(node) if node.levels[level].load_tag() != 0 => {
+                            after = NodeRef::from_maybe_tagged(&node.levels[level]);
+                            run += 1;
+                        }
+                        _ => break,
+                    }
+                }
 
-    pub fn key(&self) -> &K {
-        // #Safety
-        //
-        // Our `HazardPointer` ensures that our pointers is valid.
-        unsafe { &self.node.as_ref().key }
-    }
+                let after_ptr = after.as_ref().map_or(core::ptr::null_mut(), |n| n.as_ptr());
 
-    pub fn remove(self) -> Option<Entry<'a, K, V>> {
-        unsafe {
-            self.node.as_ref().set_removed().ok()?;
+                // Should `curr` have changed in the meantime, we start over at this level.
+                if curr.levels[level].compare_exchange(first.as_ptr(), after_ptr).is_err() {
+                    curr = head();
+                    continue;
+                }
 
-            self.node.as_ref().tag_levels(1).expect("no tags to exists");
+                // Each node of the run still holds a reference for this level, which keeps the
+                // next one alive until we release it.
+                let mut node = Some(first);
 
-            Some(self)
-            
+                for _ in 0..run {
+                    let Some(unlinked) = node else {
+                        unreachable!("the run was linked when we detached it")
+                    };
+
+                    node = NodeRef::from_maybe_tagged(&unlinked.levels[level]);
+                    self.sub_ref(&unlinked);
+                }
+            }
         }
+
+        self.garbage.domain.eager_reclaim();
     }
-}
 
-impl<'a, K, V> core::ops::Deref for Entry<'a, K, V> {
-    type Target = Node<K, V>;
+    /// Removes all entries from the list at once.
+    ///
+    /// The entries are detached from the head with a single exchange on its base level, so other
+    /// threads see either all of them or none. Entries inserted after that are kept. The detached
+    /// nodes are then unlinked from one another and retired through the hazard pointer domain, so
+    /// [Entries](Entry) held by other threads stay valid.
+    pub fn clear(&self) {
+        let head = unsafe { self.head.as_ref() };
+        let detached = Head::<K, V>::new();
+        let detached_ref = unsafe { detached.as_ref() };
+
+        // The upper levels go first, so that searches starting from here on walk the base level,
+        // which still holds all entries until we exchange it.
+        for level in (1..HEIGHT).rev() {
+            detached_ref.levels[level].store_ptr(head.levels[level].swap_ptr(core::ptr::null_mut()).0);
+        }
 
-    fn deref(&self) -> &Self::Target {
-        unsafe { self.node.as_ref() }
-    }
-}
+        detached_ref.levels[0].store_ptr(head.levels[0].swap_ptr(core::ptr::null_mut()).0);
 
-struct SearchResult<'a, K, V> {
-    prev: [(NodeRef<'a, K, V>, Option<NodeRef<'a, K, V>>); HEIGHT],
-    target: Option<NodeRef<'a, K, V>>,
-}
+        // Removing the detached nodes stops anyone from linking behind them. Concurrent removers
+        // keep their nodes and account for them, we only help tagging those.
+        let mut removed = 0;
+        let mut next = detached_ref.levels[0].load_ptr();
 
-impl<'a, K, V> Debug for SearchResult<'a, K, V>
-where
-    K: Debug + Default,
-    V: Debug,
-{
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.debug_struct("SearchResult")
-            .field("target", &self.target)
-            .finish()
-    }
-}
+        // # Safety
+        //
+        // Every node holds a reference for its base level. Once its predecessor is tagged, only we
+        // release that reference, so the nodes we come across stay valid.
+        while let Some(node) = unsafe { next.as_ref() } {
+            if node.set_removed().is_ok() {
+                // A node replaced before we tagged it is not counted, its replacement follows it.
+                if node.tag_levels(1).is_ok() {
+                    removed += 1;
+                }
+            } else {
+                let _ = node.tag_levels(1);
+            }
 
-impl<'a, K, V> Borrow<K> for Entry<'a, K, V> {
-    fn borrow(&self) -> &K {
-        unsafe { &self.node.as_ref().key }
-    }
-}
+            next = node.levels[0].load_ptr();
+        }
 
-impl<'a, K, V> AsRef<V> for Entry<'a, K, V> {
-    fn as_ref(&self) -> &V {
-        unsafe { &self.node.as_ref().val }
-    }
-}
+        self.state.len.fetch_sub(removed, Ordering::AcqRel);
 
-#[allow(dead_code)]
-struct NodeRef<'a, K, V> {
+        // # Safety
+        // 1. Nobody else knows of `detached`, it is freed below.
+        unsafe { self.unlink_removed_from(detached, HEIGHT) };
+
+        // Inserters working from an outdated search may still have linked live nodes behind the
+        // detached ones. Those are linked at the base level of the list though, so we only drop
+        // our links to them.
+        for level in 0..HEIGHT {
+            let (node, _) = detached_ref.levels[level].swap_ptr(core::ptr::null_mut());
+
+            if !node.is_null() {
+                self.sub_ref(&NodeRef::from_raw(node));
+            }
+        }
+
+        unsafe { Head::drop(detached) };
+
+        // Such inserters may also have linked detached nodes to the head again.
+        self.unlink_all_removed();
+
+        // Lower the maximum height to the levels still in use, unless another thread raised it.
+        let max_height = self.state.max_height.load(Ordering::Relaxed);
+        let mut height = max_height;
+
+        while height > 1 && head.levels[height - 1].load_ptr().is_null() {
+            height -= 1;
+        }
+
+        let _ = self.state.max_height.compare_exchange(max_height, height, Ordering::Relaxed, Ordering::Relaxed);
+    }
+
+    /// Logically removes the node from the list by linking its adjacent nodes to one-another.
+    ///
+    /// # Safety
+    /// 1. All indices in [0, height) are valid indices for `node.levels`.
+    unsafe fn unlink<'a>(
+        &self,
+        node: &'a NodeRef<'a, K, V>,
+        height: usize,
+        previous_nodes: &[(NodeRef<'a, K, V>, Option<NodeRef<'a, K, V>>); HEIGHT],
+    ) -> Result<(), usize> {
+        // safety check against UB caused by unlinking the head
+        if self.is_head(node.as_ptr()) {
+            panic!()
+        }
+
+        // The caller owns the removal of `node`, so it leaves the list even if we fail to unlink
+        // it here and a later search has to finish the job.
+        self.state.len.fetch_sub(1, Ordering::AcqRel);
+
+        // # Safety
+        //
+        // 1.-3. Some as method and covered by method caller.
+        // 4. We are not unlinking the head. - Covered by previous safety check.
+        for (i, (prev, next)) in previous_nodes.iter().enumerate().take(height).rev() {
+            let (new_next, _tag) = node.levels[i].load_decomposed();
+            let _next_ptr = next.as_ref().map_or(core::ptr::null_mut(), |n| n.as_ptr());
+
+            // We check if the previous node is being removed after we have already unlinked
+            // from it as the prev nodes expects us to do this.
+            // We still need to stop the unlink here, as we will have to relink to the actual,
+            // lively previous node at this level as well.
+
+            // Performs a compare_exchange, expecting the old value of the pointer to be the current
+            // node. If it is not, we cannot make any reasonable progress, so we search again.
+            if let Err((_other, _tag)) = prev.levels[i].compare_exchange(
+                node.as_ptr(),
+                new_next,
+            ) {
+                return Err(i + 1);
+            }
+
+            if self.sub_ref(&node).is_none() {
+                break;
+            };
+        }
+
+        // we see if we can drop some pointers in the list.
+        self.garbage.domain.eager_reclaim();
+        Ok(())
+    }
+
+    /// Decrements the reference count of the `Node` by 1. If the reference count is thus 0, we
+    /// retire the node.
+    fn sub_ref<'a>(&self, node: &NodeRef<'a, K, V>) -> Option<()> {
+        if node.try_sub_ref().expect("to not overflow") == 0 {
+            self.retire_node(node.as_ptr());
+            None
+        } else {
+            Some(())
+        }
+    }
+
+    /// Unlink [Node](Node) `curr` at the given level of [Node](Node) `prev` by exchanging the pointer for `next`.
+    ///
+    /// # Safety
+    ///
+    /// 1. `prev`, `curr`, are protected accesses.
+    #[allow(unused)]
+    unsafe fn unlink_level<'a>(
+        &'a self,
+        prev: &NodeRef<'a, K, V>,
+        curr: NodeRef<'a, K, V>,
+        next: Option<NodeRef<'a, K, V>>,
+        level: usize,
+    ) -> Result<Option<NodeRef<'a, K, V>>, ()> {
+        // The pointer to `next` is tagged to signal unlinking. 
+        let next_ptr = next.as_ref().map_or(core::ptr::null_mut(), |n| n.as_ptr());
+
+        if let Ok(_) = prev.levels[level].compare_exchange(curr.as_ptr(), next_ptr) {
+            self.sub_ref(&curr);
+
+            Ok(next)
+        } else {
+            Err(())
+        }
+    }
+
+    fn retire_node(&self, node_ptr: *mut Node<K, V>) {
+        unsafe {
+            self.garbage
+                .domain
+                .retire_ptr::<Node<K, V>, DeallocOnDrop<K, V>>(node_ptr)
+        };
+    }
+
+    fn find<'a, Q>(&'a self, key: &Q, search_closest: bool) -> SearchResult<'a, K, V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized,
+        C: Comparator<Q>,
+    {
+        let mut search = self.find_by(|k| self.compare(k, key).is_lt());
+
+        if !search_closest {
+            // With duplicate keys, live nodes of the same key may follow a removed one.
+            while let Some(target) = search.target.take() {
+                if self.compare(&target.key, key).is_ne() {
+                    break;
+                }
+
+                // Nodes are taken out of the list by tagging their base level.
+                if target.levels[0].load_tag() == 0 {
+                    search.target = Some(target);
+                    break;
+                }
+
+                search.target = match NodeRef::from_untagged(&target.levels[0]) {
+                    Ok(next) => next,
+                    Err(()) => self.find_by(|k| self.compare(k, key).is_lt()).target,
+                };
+            }
+        }
+
+        search
+    }
+
+    /// Searches for the first [Node](Node) whose key is not `before` the target, i.e. the
+    /// closest successor. `before` has to be monotonic over the ordering of the list.
+    ///
+    /// The `prev` nodes of the result are the last nodes at each level whose keys are `before`
+    /// the target, and may be the head.
+    fn find_by<'a, F>(&'a self, before: F) -> SearchResult<'a, K, V>
+    where
+        F: Fn(&K) -> bool,
+    {
+        let head = unsafe { &(*self.head.as_ptr()) };
+
+        // Initialize the `prev` array.
+        let mut prev = unsafe {
+            let mut prev: [core::mem::MaybeUninit<(NodeRef<'a, K, V>, Option<NodeRef<'a, K, V>>)>; HEIGHT] 
+                = core::mem::MaybeUninit::uninit().assume_init();
+
+            for (i, level) in prev.iter_mut().enumerate() {
+                core::ptr::write(
+                    level.as_mut_ptr(), 
+                    (NodeRef::from_raw(self.head.cast::<Node<K, V>>().as_ptr()), NodeRef::from_maybe_tagged(&self.head.as_ref().levels[i]))
+                )
+            }
+
+            core::mem::transmute::<_, [(NodeRef<'a, K, V>, Option<NodeRef<'a, K, V>>); HEIGHT]>(prev)
+        };
+
+
+        '_search: loop {
+            let mut level = self.state.max_height.load(Ordering::Relaxed);
+            // Find the first and highest node tower
+            while level > 1 && head.levels[level - 1].load_ptr().is_null() {
+                level -= 1;
+            }
+
+            // We need not protect the head, as it will always be valid, as long as we are in a sane
+            // state.
+            let mut curr = NodeRef::from_raw(self.head.as_ptr().cast::<Node<K, V>>());
+
+            // steps:
+            // 1. Go through each level until we reach a node with a key GEQ to ours or that is null
+            //     1.1 If we are equal, then the node must either be marked as removed or removed nodes
+            //       are allowed in this search.
+            //       Should this be the case, then we drop down a level while also protecting a pointer
+            //       to the current node, in order to keep the `Level` valid in our `prev` array.
+            //     1.2 If we the `next` node is less or equal but removed and removed nodes are
+            //       disallowed, then we set our current node to the next node.
+            while level > 0 {
+                let next = unsafe {
+                    // If `curr` is being removed at this level, its pointer can not be trusted.
+                    // We help tagging its other levels, so that the next search moves past it.
+                    let Ok(mut next) = NodeRef::from_untagged(&curr.levels[level - 1]) else {
+                        let _ = curr.tag_levels(1);
+                        continue '_search;
+                    };
+                    loop {
+                        if next.is_none() {
+                            break next;
+                        }
+
+                        if let Some(n) = next.as_ref() {
+                            if n.levels[level - 1].load_tag() == 0 {
+                                break next;
+                            }
+                        }
+
+                        let n = next.unwrap();
+
+                        let new_next = NodeRef::from_maybe_tagged(&n.levels[level - 1]);
+
+                        let Ok(n) = self.unlink_level(&curr, n, new_next, level - 1) else {
+                            continue '_search;
+                        };
+
+                        next = n
+
+                    }
+                };
+
+                match next {
+                    Some(next) 
+                        // This check should ensure that we always get a non-removed node, if there
+                        // is one, of our target key, as long as allow removed is set to false.
+                        if before(&next.key) => {
+
+                        // If the current node is being removed, we try to help unlinking it at this level.
+                        // Update previous_nodes.
+                        prev[level - 1] = (curr, Some(next.clone()));
+
+                        curr = next;
+                    },
+                    next => {
+                        // Update previous_nodes.
+                        prev[level - 1] = (curr.clone(), next);
+
+                        level -= 1;
+                    }
+                }
+            }
+
+            unsafe {
+                let Ok(mut next) = NodeRef::from_untagged(&curr.levels[0]) else {
+                    let _ = curr.tag_levels(1);
+                    continue '_search;
+                };
+                loop {
+                    if next.is_none() {
+                        break;
+                    }
+
+                    if let Some(n) = next.as_ref() {
+                        if n.levels[0].load_tag() == 0 {
+                            break;
+                        }
+                    }
+
+                    let n = next.unwrap();
+
+                    let new_next = NodeRef::from_maybe_tagged(&n.levels[0]);
+
+                    let Ok(n) = self.unlink_level(&curr, n, new_next, 0) else {
+                        continue '_search;
+                    };
+
+                    next = n
+                }
+
+                return SearchResult { prev, target: next };
+            }
+        }
+    }
+
+    /// Hands out `node` as an [Entry](Entry) of this list.
+    fn to_entry<'a>(&'a self, node: NodeRef<'a, K, V>) -> Entry<'a, K, V> {
+        Entry { node, list: self }
+    }
+
+    pub fn get<'a, Q>(&'a self, key: &Q) -> Option<Entry<'a, K, V>>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized,
+        C: Comparator<Q>,
+    {
+        if self.is_empty() {
+            return None;
+        }
+
+        // Perform safety check for whether we are dealing with the head.
+        match self.find(key, false) {
+            SearchResult {
+                target: Some(target),
+                ..
+            } => Some(self.to_entry(target)),
+            _ => None,
+        }
+    }
+
+    /// Returns `true` if the list contains an entry for the given `key`.
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: ?Sized,
+        C: Comparator<Q>,
+    {
+        self.get(key).is_some()
+    }
+
+    /// Returns the first [Entry](Entry) whose key is greater than or equal to `key`.
+    pub fn lower_bound<'a, Q>(&'a self, key: &Q) -> Option<Entry<'a, K, V>>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized,
+        C: Comparator<Q>,
+    {
+        if self.is_empty() {
+            return None;
+        }
+
+        self.find(key, true).target.map(|t| self.to_entry(t))
+    }
+
+    /// Returns the first [Entry](Entry) whose key is strictly greater than `key`.
+    pub fn upper_bound<'a, Q>(&'a self, key: &Q) -> Option<Entry<'a, K, V>>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized,
+        C: Comparator<Q>,
+    {
+        if self.is_empty() {
+            return None;
+        }
+
+        self.find_by(|k| self.compare(k, key).is_le()).target.map(|t| self.to_entry(t))
+    }
+
+    /// Returns the last [Entry](Entry) whose key is less than or equal to `key`.
+    pub fn floor<'a, Q>(&'a self, key: &Q) -> Option<Entry<'a, K, V>>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized,
+        C: Comparator<Q>,
+    {
+        self.find_last_by(|k| self.compare(k, key).is_le())
+    }
+
+    /// Returns the first [Entry](Entry) whose key is greater than or equal to `key`.
+    ///
+    /// This is the same as [lower_bound](SkipList::lower_bound).
+    pub fn ceiling<'a, Q>(&'a self, key: &Q) -> Option<Entry<'a, K, V>>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized,
+        C: Comparator<Q>,
+    {
+        self.lower_bound(key)
+    }
+
+    /// Finds the last live [Entry](Entry) whose key is `before` the target. See
+    /// [find_by](SkipList::find_by).
+    fn find_last_by<'a, F>(&'a self, before: F) -> Option<Entry<'a, K, V>>
+    where
+        F: Fn(&K) -> bool,
+    {
+        if self.is_empty() {
+            return None;
+        }
+
+        loop {
+            let [(prev, _), ..] = self.find_by(&before).prev;
+
+            if self.is_head(prev.as_ptr()) {
+                return None;
+            }
+
+            // If the predecessor has been removed, we search again to find the live one.
+            if prev.levels[0].load_tag() == 0 {
+                return Some(self.to_entry(prev));
+            }
+        }
+    }
+
+    fn is_head(&self, ptr: *const Node<K, V>) -> bool {
+        std::ptr::eq(ptr, self.head.as_ptr().cast())
+    }
+
+    /// Finds the first live [Entry](Entry) whose key is greater than that of `node`, whether or not
+    /// `node` has been removed in the meantime. See [Cursor](Cursor) for the public version of
+    /// this contract.
+    fn next_node<'a>(&'a self, node: &Entry<'a, K, V>) -> Option<Entry<'a, K, V>> {
+        let node = &node.node;
+
+        // The head has no key to search past, its successor is the first node of the list.
+        let is_head = self.is_head(node.as_ptr());
+        let search = || if is_head {
+            self.find_by(|_| false).target
+        } else {
+            self.find_by(|k| self.compare(k, &node.key).is_le()).target
+        };
+
+        let mut next = match NodeRef::from_untagged(&node.levels[0]) {
+            Ok(next) => next?,
+            // This means we have a stale node and cannot return a sane answer!
+            Err(()) => return search().map(|t| self.to_entry(t)),
+        };
+        
+        // Unlink and skip all removed `Node`s we may encounter.
+        while next.levels[0].load_tag() != 0 {
+            let new = NodeRef::from_maybe_tagged(&next.levels[0]);
+            next = unsafe {
+                self.unlink_level(&node, next, new, 0)
+                    .ok()
+                    .unwrap_or_else(search)?
+            };
+        }
+
+        Some(self.to_entry(next))
+    }
+
+    pub fn get_first<'a>(&'a self) -> Option<Entry<'a, K, V>> {
+        if self.is_empty() {
+            return None;
+        }
+
+        let curr = NodeRef::from_raw(self.head.as_ptr().cast::<Node<K, V>>());
+
+        self.next_node(&self.to_entry(curr))
+    }
+
+    /// Finds the live [Entry](Entry) preceding `node`. Unlike [next_node](SkipList::next_node),
+    /// this requires a search from the top of the list.
+    fn prev_node<'a>(&'a self, node: &Entry<'a, K, V>) -> Option<Entry<'a, K, V>> {
+        self.prev_run(node).pop()
+    }
+
+    /// Finds the live [Entries](Entry) preceding `node` that share its key, in order. If there
+    /// are none, this is the live `Entry` preceding the key instead, if any.
+    ///
+    /// Duplicates can not be told apart by searching, so we walk them from the front and keep
+    /// every one we pass until we reach `node` itself. Handing out the whole run lets iterators
+    /// step back over `d` duplicates in O(d), rather than walking them again for every step.
+    fn prev_run<'a>(&'a self, node: &Entry<'a, K, V>) -> Vec<Entry<'a, K, V>> {
+        let before = self.find_last_by(|k| self.compare(k, node.key()).is_lt());
+        let mut run = Vec::new();
+
+        let mut next = match before.as_ref() {
+            Some(before) => self.next_node(before),
+            None => self.get_first(),
+        };
+
+        while let Some(curr) = next {
+            if curr.is(node) {
+                if run.is_empty() {
+                    run.extend(before);
+                }
+
+                return run;
+            }
+
+            if self.compare(curr.key(), node.key()).is_ne() {
+                break;
+            }
+
+            next = self.next_node(&curr);
+            run.push(curr);
+        }
+
+        // `node` is no longer linked, so we can not tell which of its duplicates preceded it.
+        // Rather than handing out any of them twice, we step back past the whole key.
+        before.into_iter().collect()
+    }
+
+    pub fn get_last<'a>(&'a self) -> Option<Entry<'a, K, V>> {
+        self.find_last_by(|_| true)
+    }
+
+    pub fn iter<'a>(&'a self) -> Iter<'a, K, V, C> {
+        Iter::from_list(self)
+    }
+
+    /// Returns an unpositioned [Cursor](Cursor) over the list.
+    pub fn cursor<'a>(&'a self) -> Cursor<'a, K, V, C> {
+        Cursor::new(self)
+    }
+
+    /// Returns an iterator over all values of `key`, in the order they were inserted. See
+    /// [insert_multi](SkipList::insert_multi).
+    pub fn get_all<'a, 'k, Q>(&'a self, key: &'k Q) -> GetAll<'a, 'k, K, V, Q, C>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized,
+        C: Comparator<Q>,
+    {
+        self.range((Bound::Included(key), Bound::Included(key)))
+    }
+
+    /// Returns an iterator over the entries whose keys lie within `range`.
+    ///
+    /// The start of the range is located with a single search, after which the iterator walks
+    /// the base level until it passes the end of the range.
+    pub fn range<'a, Q, R>(&'a self, range: R) -> Range<'a, K, V, R, Q, C>
+    where
+        K: Borrow<Q>,
+        R: RangeBounds<Q>,
+        Q: ?Sized,
+        C: Comparator<Q>,
+    {
+        Range::from_list(self, range)
+    }
+
+    /// Finds the last [Entry](Entry) that lies before the given `bound`.
+    fn seek_back<'a, Q>(&'a self, bound: Bound<&Q>) -> Option<Entry<'a, K, V>>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized,
+        C: Comparator<Q>,
+    {
+        match bound {
+            Bound::Included(key) => self.floor(key),
+            Bound::Excluded(key) => self.find_last_by(|k| self.compare(k, key).is_lt()),
+            Bound::Unbounded => self.get_last(),
+        }
+    }
+
+    /// Finds the first [Entry](Entry) that lies after the given `bound`.
+    fn seek<'a, Q>(&'a self, bound: Bound<&Q>) -> Option<Entry<'a, K, V>>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized,
+        C: Comparator<Q>,
+    {
+        match bound {
+            Bound::Included(key) => self.lower_bound(key),
+            Bound::Excluded(key) => self.upper_bound(key),
+            Bound::Unbounded => self.get_first(),
+        }
+    }
+}
+
+impl<K, V> Default for SkipList<K, V>
+where
+    K: Sync,
+    V: Sync,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+unsafe impl<K, V, C> Send for SkipList<K, V, C>
+where
+    K: Send + Sync,
+    V: Send + Sync,
+    C: Send + Sync,
+{
+}
+
+unsafe impl<K, V, C> Sync for SkipList<K, V, C>
+where
+    K: Send + Sync,
+    V: Send + Sync,
+    C: Send + Sync,
+{
+}
+
+impl<K, V, C> crate::skiplist::SkipList<K, V> for SkipList<K, V, C>
+where
+    K: Send + Sync,
+    V: Send + Sync,
+    C: Comparator<K> + Default,
+{
+    type Entry<'a> = Entry<'a, K, V> where Self: 'a;
+
+    type Removed<'a> = Entry<'a, K, V> where Self: 'a;
+
+    type Iter<'a> = Iter<'a, K, V, C> where Self: 'a;
+
+    type Range<'a, R> = Range<'a, K, V, R, K, C> where Self: 'a, R: RangeBounds<K> + 'a;
+
+    fn new() -> Self {
+        Self::with_comparator(C::default())
+    }
+
+    fn insert(&mut self, key: K, value: V) -> Option<Entry<'_, K, V>> {
+        SkipList::insert(self, key, value)
+    }
+
+    fn get<'a>(&'a self, key: &K) -> Option<Entry<'a, K, V>> {
+        SkipList::get(self, key)
+    }
+
+    fn remove(&mut self, key: &K) -> Option<Entry<'_, K, V>> {
+        SkipList::remove(self, key)
+    }
+
+    fn front<'a>(&'a self) -> Option<Entry<'a, K, V>> {
+        self.get_first()
+    }
+
+    fn last<'a>(&'a self) -> Option<Entry<'a, K, V>> {
+        self.get_last()
+    }
+
+    fn iter<'a>(&'a self) -> Iter<'a, K, V, C> {
+        SkipList::iter(self)
+    }
+
+    fn range<'a, R>(&'a self, range: R) -> Range<'a, K, V, R, K, C>
+    where
+        R: RangeBounds<K> + 'a,
+    {
+        SkipList::range(self, range)
+    }
+
+    fn len(&self) -> usize {
+        SkipList::len(self)
+    }
+}
+
+impl<'a, K, V> crate::skiplist::Entry<'a, K, V> for Entry<'a, K, V> {
+    fn val(&self) -> &V {
+        Entry::val(self)
+    }
+
+    fn key(&self) -> &K {
+        Entry::key(self)
+    }
+}
+
+/// The nodes of the single-threaded list keep the spans of their levels, which ours do not, so
+/// the conversion moves every key and value into a new node. This takes O(n), but no searches.
+impl<K, V, C> From<super::skiplist::SkipList<K, V, C>> for SkipList<K, V, C>
+where
+    K: Sync,
+    V: Sync,
+{
+    fn from(list: super::skiplist::SkipList<K, V, C>) -> Self {
+        type Spanned<K, V> = crate::internal::utils::Node<K, V, core::sync::atomic::AtomicUsize>;
+
+        let list = core::mem::ManuallyDrop::new(list);
+
+        unsafe {
+            let new_list = SkipList {
+                head: crate::internal::utils::Head::new(),
+                state: core::ptr::read(&list.state),
+                comparator: core::ptr::read(&list.comparator),
+                garbage: core::ptr::read(&list.garbage),
+            };
+
+            // # Safety
+            //
+            // We own `list`, which is never dropped, so each of its nodes is read from exactly once
+            // before it is deallocated without dropping its key and value. Each new node gets the
+            // same height and reference count, as it is linked on the same levels.
+            let mut last = [new_list.head.as_ptr().cast::<Node<K, V>>(); HEIGHT];
+            let mut node = (&(*list.head.as_ptr()).levels)[0].load_ptr();
+
+            while !node.is_null() {
+                let height = (*node).height();
+                let new_node = Node::<K, V>::alloc(height);
+
+                core::ptr::write(&mut (*new_node).key, core::ptr::read(&(*node).key));
+                core::ptr::write(&mut (*new_node).val, core::ptr::read(&(*node).val));
+                (*new_node).height_and_removed.store(
+                    (*node).height_and_removed.load(Ordering::Relaxed),
+                    Ordering::Relaxed,
+                );
+
+                for (i, prev) in last.iter_mut().enumerate().take(height) {
+                    (&(**prev).levels)[i].store_ptr(new_node);
+                    *prev = new_node;
+                }
+
+                let next = (&(*node).levels)[0].load_ptr();
+                Spanned::<K, V>::dealloc(node);
+                node = next;
+            }
+
+            crate::internal::utils::Head::drop(list.head);
+
+            new_list
+        }
+    }
+}
+
+
+pub struct Entry<'a, K, V> {
+    node: NodeRef<'a, K, V>,
+    list: &'a dyn RemoveEntry<'a, K, V>,
+}
+
+/// Lets an [Entry](Entry) remove itself from the list it was taken from, without carrying the
+/// comparator of that list in its type.
+trait RemoveEntry<'a, K, V> {
+    fn remove_entry(&'a self, entry: &Entry<'a, K, V>) -> Option<Entry<'a, K, V>>;
+}
+
+impl<'a, K, V, C> RemoveEntry<'a, K, V> for SkipList<K, V, C>
+where
+    K: Send + Sync,
+    V: Send + Sync,
+    C: Comparator<K>,
+{
+    fn remove_entry(&'a self, entry: &Entry<'a, K, V>) -> Option<Entry<'a, K, V>> {
+        SkipList::remove_entry(self, entry)
+    }
+}
+
+impl<'a, K, V> Entry<'a, K, V> {
+    pub fn val(&self) -> &V {
+        // #Safety
+        //
+        // Our `HazardPointer` ensures that our pointers is valid.
+        &self.node.val
+    }
+
+    pub fn key(&self) -> &K {
+        // #Safety
+        //
+        // Our `HazardPointer` ensures that our pointers is valid.
+        &self.node.key
+    }
+
+    /// Checks whether both entries refer to the same [Node](Node).
+    pub(crate) fn is(&self, other: &Self) -> bool {
+        self.node == other.node
+    }
+
+    /// Removes this entry from the list it was taken from. Returns `None` if the entry has been
+    /// removed already.
+    pub fn remove(self) -> Option<Entry<'a, K, V>> {
+        self.list.remove_entry(&self)
+    }
+}
+
+impl<'a, K, V> Clone for Entry<'a, K, V> {
+    fn clone(&self) -> Self {
+        Entry { node: self.node.clone(), list: self.list }
+    }
+}
+
+impl<'a, K, V> core::ops::Deref for Entry<'a, K, V> {
+    type Target = Node<K, V>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.node
+    }
+}
+
+struct SearchResult<'a, K, V> {
+    prev: [(NodeRef<'a, K, V>, Option<NodeRef<'a, K, V>>); HEIGHT],
+    target: Option<NodeRef<'a, K, V>>,
+}
+
+impl<'a, K, V> Debug for SearchResult<'a, K, V>
+where
+    K: Debug + Default,
+    V: Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SearchResult")
+            .field("target", &self.target)
+            .finish()
+    }
+}
+
+impl<'a, K, V> Borrow<K> for Entry<'a, K, V> {
+    fn borrow(&self) -> &K {
+        &self.node.key
+    }
+}
+
+impl<'a, K, V> AsRef<V> for Entry<'a, K, V> {
+    fn as_ref(&self) -> &V {
+        &self.node.val
+    }
+}
+
+#[allow(dead_code)]
+struct NodeRef<'a, K, V> {
     node: NonNull<Node<K, V>>,
     _hazard: HazardPointer<'a>
 }
 
-impl<'a, K, V> NodeRef<'a, K, V> {
-    fn from_raw_in(ptr: *mut Node<K, V>, domain: &'a Domain<Global>) -> Self {
-        let mut _hazard = HazardPointer::new_in_domain(domain);
-        _hazard.protect_raw(ptr);
-        unsafe {
-            NodeRef { node: NonNull::new_unchecked(ptr), _hazard }
+impl<'a, K, V> NodeRef<'a, K, V> {
+    fn from_raw_in(ptr: *mut Node<K, V>, domain: &'a Domain<Global>) -> Self {
+        let mut _hazard = HazardPointer::new_in_domain(domain);
+        _hazard.protect_raw(ptr);
+        unsafe {
+            NodeRef { node: NonNull::new_unchecked(ptr), _hazard }
+        }
+    }
+
+    fn from_raw(ptr: *mut Node<K, V>) -> Self {
+        Self::from_raw_in(ptr, Domain::global())
+    }
+
+    fn as_ptr(&self) -> *mut Node<K, V> {
+        self.node.as_ptr()
+    }
+}
+
+impl<'a, K, V> AsRef<Node<K, V>> for NodeRef<'a, K, V> {
+    fn as_ref(&self) -> &Node<K, V> {
+        unsafe { &(*self.as_ptr()) }
+    }
+}
+
+impl<'a, K, V> core::ops::Deref for NodeRef<'a, K, V> {
+    type Target = Node<K, V>;
+    fn deref(&self) -> &Self::Target {
+        self.as_ref()
+    }
+}
+
+impl<'a, K, V> core::ops::DerefMut for NodeRef<'a, K, V> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        unsafe { &mut (*self.as_ptr()) }
+    }
+}
+
+impl<'a, K, V> core::fmt::Debug for NodeRef<'a, K, V> 
+where 
+    K: Debug, 
+    V: Debug 
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        unsafe {
+            f.debug_struct("NodeRef").field("node", self.node.as_ref()).finish()
+        }
+    }
+}
+
+impl<'a, K, V> Clone for NodeRef<'a, K, V> {
+    fn clone(&self) -> Self {
+        let mut _hazard = HazardPointer::new();
+        _hazard.protect_raw(self.node.as_ptr());
+
+        NodeRef { node: self.node.clone(), _hazard }
+    }
+}
+
+impl<'a, K, V> core::cmp::PartialEq for NodeRef<'a, K, V> {
+    fn eq(&self, other: &Self) -> bool {
+        core::ptr::eq(self.node.as_ptr(), other.node.as_ptr())
+    }
+}
+
+impl<'a, K, V> core::cmp::Eq for NodeRef<'a, K, V> {}
+
+#[repr(transparent)]
+struct DeallocOnDrop<K, V>(*mut Node<K, V>);
+
+unsafe impl<K, V> Send for DeallocOnDrop<K, V> 
+where K: Send + Sync,
+      V: Send + Sync
+{
+}
+
+unsafe impl<K, V> Sync for DeallocOnDrop<K, V> 
+where K: Send + Sync,
+      V: Send + Sync
+{
+}
+
+impl<K, V> From<*mut Node<K, V>> for DeallocOnDrop<K, V> {
+    fn from(node: *mut Node<K, V>) -> Self {
+        DeallocOnDrop(node)
+    }
+}
+
+impl<K, V> Drop for DeallocOnDrop<K, V> {
+    fn drop(&mut self) {
+        unsafe {
+            Node::drop(self.0)
+        }
+    }
+}
+
+unsafe impl<K, V> Pointer<Node<K, V>> for DeallocOnDrop<K, V> {
+    fn into_raw(self) -> *mut Node<K, V> {
+        self.0
+    }
+
+    unsafe fn from_raw(ptr: *mut Node<K, V>) -> Self {
+        DeallocOnDrop::from(ptr)
+    }
+}
+
+impl<K, V> core::ops::Deref for DeallocOnDrop<K, V> {
+    type Target = Node<K, V>;
+
+    fn deref(&self) -> &Self::Target {
+        unsafe { &(*self.0) }
+    }
+}
+
+impl<K, V> core::ops::DerefMut for DeallocOnDrop<K, V> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        unsafe {&mut (*self.0)}
+    }
+}
+
+/// Retires a node without dropping it. Reclaiming it hands it back to the thread in
+/// [take_removed](SkipList::take_removed), or frees it if that thread has given up on it.
+///
+/// A node is retired this way once it is unlinked and has no references left. Two parties then
+/// each add one reference: the domain when it drops the `ReleaseOnDrop`, which it only does once
+/// no hazard pointer protects the node, and `take_removed` after it protected the node again.
+/// Neither frees the node before the other has added its reference, and whoever comes second
+/// owns it:
+///
+/// 1. If `take_removed` comes second, nobody protected the node when it was reclaimed, so it moves
+///    the key and value out and frees the node.
+/// 2. If the domain comes second, `take_removed` has handed the node back in an [Entry], so the
+///    node is retired once more, to be freed after that `Entry` and any other is dropped.
+#[repr(transparent)]
+struct ReleaseOnDrop<K, V>(*mut Node<K, V>)
+where K: Send + Sync,
+      V: Send + Sync;
+
+impl<K, V> Drop for ReleaseOnDrop<K, V>
+where K: Send + Sync,
+      V: Send + Sync
+{
+    fn drop(&mut self) {
+        unsafe {
+            if (*self.0).add_ref() == 1 {
+                // The node may still be protected by the entry handed back instead, so it is
+                // retired once more to be freed after that is dropped.
+                Domain::global().retire_ptr::<Node<K, V>, DeallocOnDrop<K, V>>(self.0);
+            }
+        }
+    }
+}
+
+unsafe impl<K, V> Pointer<Node<K, V>> for ReleaseOnDrop<K, V>
+where K: Send + Sync,
+      V: Send + Sync
+{
+    fn into_raw(self) -> *mut Node<K, V> {
+        self.0
+    }
+
+    unsafe fn from_raw(ptr: *mut Node<K, V>) -> Self {
+        ReleaseOnDrop(ptr)
+    }
+}
+
+impl<K, V> core::ops::Deref for ReleaseOnDrop<K, V>
+where K: Send + Sync,
+      V: Send + Sync
+{
+    type Target = Node<K, V>;
+
+    fn deref(&self) -> &Self::Target {
+        unsafe { &(*self.0) }
+    }
+}
+
+#[cfg(test)]
+mod sync_test {
+    use rand::Rng;
+
+    use super::*;
+
+    #[test]
+    fn test_new_node_sync() {
+        let node: *mut Node<_, _> = Node::new(100, "hello", 1);
+        let other: *mut Node<_, _> = Node::new(100, "hello", 1);
+        unsafe { println!("node 1: {:?},", *node) };
+        unsafe { println!("node 2: {:?},", *other) };
+        let other = unsafe {
+            let node = Node::alloc(1);
+            core::ptr::write(&mut (*node).key, 100);
+            core::ptr::write(&mut (*node).val, "hello");
+            node
+        };
+
+        unsafe { println!("node 1: {:?}, node 2: {:?}", *node, *other) };
+
+        unsafe { assert_eq!(*node, *other) };
+    }
+
+    #[test]
+    fn test_new_list_sync() {
+        let _: SkipList<usize, usize> = SkipList::new();
+    }
+
+    #[test]
+    fn test_static_list() {
+        fn assert_static<T: 'static>(_: &T) {}
+
+        let list = SkipList::new();
+        list.insert(1, "a");
+        assert_static(&list);
+
+        let handle = std::thread::spawn(move || {
+            list.insert(2, "b");
+            list
+        });
+
+        let list = handle.join().unwrap();
+        assert_eq!(list.len(), 2);
+    }
+
+    #[test]
+    fn test_insert_sync() {
+        let list = SkipList::new();
+        let mut rng: u16 = rand::random();
+
+        for _ in 0..10_000 {
+            rng ^= rng << 3;
+            rng ^= rng >> 12;
+            rng ^= rng << 7;
+            list.insert(rng, "hello there!");
+        }
+    }
+
+    #[test]
+    fn test_rand_height_sync() {
+        let mut list: SkipList<i32, i32> = SkipList::new();
+        let node: *mut Node<_, _> = Node::new_rand_height("Hello", "There!", &mut list);
+
+        assert!(!node.is_null());
+        let height = unsafe { (*node).levels.pointers.len() };
+
+        println!("height: {}", height);
+
+        unsafe {
+            println!("{}", *node);
+        }
+
+        unsafe {
+            let _ = Box::from_raw(node);
+        }
+    }
+
+    #[test]
+    fn test_drop() {
+        struct CountOnDrop<K> {
+            key: K,
+            counter: std::sync::Arc<std::sync::atomic::AtomicUsize>,
+        }
+
+        impl<K> CountOnDrop<K> {
+            fn new(key: K, counter: std::sync::Arc<std::sync::atomic::AtomicUsize>) -> Self {
+                CountOnDrop { key, counter }
+            }
+
+            fn new_none(key: K) -> Self {
+                CountOnDrop { key, counter: std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0)) }
+            }
+        }
+        impl<K: Eq> PartialEq for CountOnDrop<K> {
+            fn eq(&self, other: &Self) -> bool {
+                self.key == other.key
+            }
+        }
+
+        impl<K: Eq> Eq for CountOnDrop<K> {}
+
+        impl<K: Ord> PartialOrd for CountOnDrop<K> {
+            fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+                Some(self.key.cmp(&other.key))
+            }
+        }
+
+        impl<K: Ord> Ord for CountOnDrop<K> {
+            fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+                self.key.cmp(&other.key)
+            }
+        }
+
+        impl<K> Drop for CountOnDrop<K> {
+            fn drop(&mut self) {
+                println!("writing to counter!");
+                println!("count: {}", self.counter.load(Ordering::SeqCst));
+                self.counter.fetch_add(1, Ordering::SeqCst);
+                println!("wrote to counter");
+            }
+        }
+
+        let counter = std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0));
+
+        let list = SkipList::new();
+
+        list.insert(CountOnDrop::new(1, counter.clone()), ());
+
+        list.remove(&CountOnDrop::new_none(1));
+
+        // assert_eq!(counter.load(Ordering::SeqCst), 1);
+
+        list.insert(CountOnDrop::new(1, counter.clone()), ());
+
+        list.insert(CountOnDrop::new(1, counter.clone()), ());
+
+        println!("length: {}", list.len());
+
+        list.garbage.domain.eager_reclaim();
+
+        core::sync::atomic::fence(Ordering::SeqCst);
+
+        assert_eq!(counter.load(Ordering::SeqCst), 2);
+
+        drop(list);
+
+        assert_eq!(counter.load(Ordering::SeqCst), 3);
+
+    }
+
+    #[test]
+    fn test_insert_verbose_sync() {
+        let list = SkipList::new();
+
+        list.insert(1, 1);
+
+        list.iter().for_each(|n| println!("k: {},", n.key()));
+
+        list.insert(2, 2);
+
+        list.iter().for_each(|n| println!("k: {},", n.key()));
+
+        list.insert(5, 3);
+
+        list.iter().for_each(|n| println!("k: {},", n.key()));
+    }
+
+    #[test]
+    fn test_remove() {
+        let list = SkipList::new();
+        let mut rng: u16 = rand::random();
+
+        for _ in 0..10_000 {
+            rng ^= rng << 3;
+            rng ^= rng >> 12;
+            rng ^= rng << 7;
+            list.insert(rng, "hello there!");
+        }
+        for _ in 0..10_000 {
+            rng ^= rng << 3;
+            rng ^= rng >> 12;
+            rng ^= rng << 7;
+            list.remove(&rng);
+        }
+    }
+
+    #[test]
+    fn test_entry_remove() {
+        let list = SkipList::new();
+
+        for i in 0..10 {
+            list.insert(i, ());
+        }
+
+        let entry = list.get(&4).unwrap();
+        assert_eq!(entry.clone().remove().map(|e| *e.key()), Some(4));
+        assert!(entry.remove().is_none());
+
+        assert_eq!(list.len(), 9);
+        assert!(list.get(&4).is_none());
+
+        assert_eq!(list.remove_first().map(|e| *e.key()), Some(0));
+        assert_eq!(list.len(), 8);
+        assert_eq!(list.iter().count(), 8);
+    }
+
+    #[test]
+    fn test_verbose_remove() {
+        let list = SkipList::new();
+
+        list.insert(1, 1);
+        list.insert(2, 2);
+        list.insert(2, 2);
+        list.insert(5, 3);
+
+        list.iter().for_each(|n| println!("k: {},", n.key()));
+
+        assert!(list.remove(&1).is_some());
+
+        list.iter().for_each(|n| println!("k: {},", n.key()));
+
+        println!("removing 6");
+        assert!(list.remove(&6).is_none());
+        println!("removing 1");
+        assert!(list.remove(&1).is_none());
+        println!("removing 5");
+        assert!(list.remove(&5).is_some());
+        println!("removing 2");
+        assert!(list.remove(&2).is_some());
+
+        list.iter().for_each(|n| println!("k: {},", n.key()));
+
+        assert_eq!(list.len(), 0);
+    }
+
+    #[test]
+    fn test_find_removed() {
+        let list = SkipList::new();
+
+        list.insert(3, ());
+
+        list.insert(4, ());
+
+        list.insert(5, ());
+
+        assert!(list.find(&3, false).target.is_some());
+        assert!(list.find(&4, false).target.is_some());
+
+        // manually get reference to the nodes
+        let node_3 = unsafe { &mut (*list.head.as_ref().levels[0].load_ptr()) };
+        let node_4 = unsafe { &mut (*node_3.levels[0].load_ptr()) };
+        let node_5 = unsafe { &mut (*node_4.levels[0].load_ptr()) };
+
+        // make sure it is the right node
+        assert_eq!(node_3.key, 3);
+        println!("{:?}", node_3);
+        assert_eq!(node_4.key, 4);
+        println!("{:?}", node_4);
+        assert_eq!(node_5.key, 5);
+        println!("{:?}", node_5);
+
+        // remove the node logically, which only takes it out of the list once its base level is
+        // tagged
+        let _ = node_4.set_removed();
+
+        assert!(list.find(&4, false).target.is_some());
+
+        println!("{:?}", list.find(&3, false));
+
+        assert!(!node_3.removed());
+
+        assert!(list.remove(&4).is_none());
+
+        // remove the node logically
+        node_4.height_and_removed.store(
+            node_4.height_and_removed.load(Ordering::SeqCst) & (usize::MAX >> 1),
+            Ordering::SeqCst,
+        );
+
+        assert!(!node_4.removed());
+
+        assert!(list.remove(&4).is_some());
+    }
+
+    #[test]
+    fn test_sync_remove() {
+        use std::sync::Arc;
+        let list = Arc::new(SkipList::new());
+        let mut rng = rand::thread_rng();
+
+        for _ in 0..10_000 {
+            list.insert(rng.gen::<u16>(), ());
+        }
+        let threads = (0..20)
+            .map(|_| {
+                let list = list.clone();
+                std::thread::spawn(move || {
+                    let mut rng = rand::thread_rng();
+                    for _ in 0..1_000 {
+                        let target = &rng.gen::<u16>();
+                        list.remove(&target);
+                    }
+                })
+            })
+            .collect::<Vec<_>>();
+
+        for thread in threads {
+            thread.join().unwrap()
+        }
+
+        list.iter().for_each(|e| println!("key: {}", e.key));
+    }
+
+    #[test]
+    fn test_sync_insert() {
+        use std::sync::Arc;
+        let list = Arc::new(SkipList::new());
+
+        let threads = (0..20)
+            .map(|_| {
+                let list = list.clone();
+                std::thread::spawn(move || {
+                    let mut rng = rand::thread_rng();
+                    for _ in 0..1_000 {
+                        let target = rng.gen::<u8>();
+
+                        list.insert(target, ());
+                    }
+                })
+            })
+            .collect::<Vec<_>>();
+
+        for thread in threads {
+            thread.join().unwrap()
+        }
+
+        list.iter().for_each(|e| println!("key: {}", e.key));
+    }
+
+    #[test]
+    fn test_sync_inmove() {
+        use std::sync::Arc;
+        let list = Arc::new(SkipList::new());
+
+        let threads = (0..20)
+            .map(|_| {
+                let list = list.clone();
+                std::thread::spawn(move || {
+                    let mut rng = rand::thread_rng();
+                    for _ in 0..5_000 {
+                        let target = rng.gen::<u8>();
+                        if rng.gen::<u8>() % 5 == 0 {
+                            list.remove(&target);
+                        } else {
+                            list.insert(target, ());
+                        }
+                    }
+                })
+            })
+            .collect::<Vec<_>>();
+
+        for thread in threads {
+            thread.join().unwrap()
         }
-    }
 
-    fn from_raw(ptr: *mut Node<K, V>) -> Self {
-        Self::from_raw_in(ptr, Domain::global())
+        list.iter().for_each(|e| println!("key: {}", e.key));
     }
 
-    fn as_ptr(&self) -> *mut Node<K, V> {
-        self.node.as_ptr()
-    }
-}
+    #[test]
+    fn test_range() {
+        let list = SkipList::new();
 
-impl<'a, K, V> AsRef<Node<K, V>> for NodeRef<'a, K, V> {
-    fn as_ref(&self) -> &Node<K, V> {
-        unsafe { &(*self.as_ptr()) }
-    }
-}
+        for i in 0..100 {
+            list.insert(i, i * 2);
+        }
 
-impl<'a, K, V> core::ops::Deref for NodeRef<'a, K, V> {
-    type Target = Node<K, V>;
-    fn deref(&self) -> &Self::Target {
-        self.as_ref()
-    }
-}
+        let keys = |range| list.range(range).map(|e| *e.key()).collect::<Vec<_>>();
 
-impl<'a, K, V> core::ops::DerefMut for NodeRef<'a, K, V> {
-    fn deref_mut(&mut self) -> &mut Self::Target {
-        unsafe { &mut (*self.as_ptr()) }
+        assert_eq!(keys((Bound::Included(10), Bound::Excluded(15))), vec![10, 11, 12, 13, 14]);
+        assert_eq!(keys((Bound::Excluded(10), Bound::Included(12))), vec![11, 12]);
+        assert_eq!(keys((Bound::Included(97), Bound::Unbounded)), vec![97, 98, 99]);
+        assert_eq!(keys((Bound::Unbounded, Bound::Excluded(3))), vec![0, 1, 2]);
+        assert_eq!(keys((Bound::Included(120), Bound::Unbounded)), vec![]);
+        assert_eq!(keys((Bound::Included(50), Bound::Excluded(50))), vec![]);
+
+        list.remove(&21);
+
+        assert_eq!(list.range(20..23).map(|e| *e.val()).collect::<Vec<_>>(), vec![40, 44]);
+        assert_eq!(list.range(..).count(), 99);
     }
-}
 
-impl<'a, K, V> core::fmt::Debug for NodeRef<'a, K, V> 
-where 
-    K: Debug, 
-    V: Debug 
-{
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        unsafe {
-            f.debug_struct("NodeRef").field("node", self.node.as_ref()).finish()
+    #[test]
+    fn test_rev() {
+        let list = SkipList::new();
+
+        for i in 0..100 {
+            list.insert(i, ());
         }
-    }
-}
 
-impl<'a, K, V> From<NodeRef<'a, K, V>> for Entry<'a, K, V> {
-    fn from(value: NodeRef<'a, K, V>) -> Self {
-        unsafe { core::mem::transmute(value) }
+        assert_eq!(*list.get_last().unwrap().key(), 99);
+        assert_eq!(list.iter().rev().take(3).map(|e| *e.key()).collect::<Vec<_>>(), vec![99, 98, 97]);
+        assert_eq!(list.iter().rev().count(), 100);
+        assert_eq!(list.range(10..15).rev().map(|e| *e.key()).collect::<Vec<_>>(), vec![14, 13, 12, 11, 10]);
+        assert_eq!(list.range(..=3).rev().map(|e| *e.key()).collect::<Vec<_>>(), vec![3, 2, 1, 0]);
+        assert_eq!(list.range(150..).rev().count(), 0);
+
+        let mut range = list.range(40..45);
+
+        assert_eq!(range.next().map(|e| *e.key()), Some(40));
+        assert_eq!(range.next_back().map(|e| *e.key()), Some(44));
+        assert_eq!(range.next_back().map(|e| *e.key()), Some(43));
+        assert_eq!(range.next().map(|e| *e.key()), Some(41));
+        assert_eq!(range.next().map(|e| *e.key()), Some(42));
+        assert!(range.next_back().is_none());
+        assert!(range.next().is_none());
+
+        list.remove(&99);
+        list.remove(&97);
+
+        assert_eq!(list.iter().rev().take(2).map(|e| *e.key()).collect::<Vec<_>>(), vec![98, 96]);
     }
-}
 
-impl<'a, K, V> Clone for NodeRef<'a, K, V> {
-    fn clone(&self) -> Self {
-        let mut _hazard = HazardPointer::new();
-        _hazard.protect_raw(self.node.as_ptr());
+    #[test]
+    fn test_bounds() {
+        let list = SkipList::new();
 
-        NodeRef { node: self.node.clone(), _hazard }
+        for i in (0..100).step_by(10) {
+            list.insert(i, ());
+        }
+
+        assert_eq!(list.lower_bound(&20).map(|e| *e.key()), Some(20));
+        assert_eq!(list.lower_bound(&21).map(|e| *e.key()), Some(30));
+        assert_eq!(list.upper_bound(&20).map(|e| *e.key()), Some(30));
+        assert_eq!(list.upper_bound(&25).map(|e| *e.key()), Some(30));
+        assert_eq!(list.floor(&20).map(|e| *e.key()), Some(20));
+        assert_eq!(list.floor(&29).map(|e| *e.key()), Some(20));
+        assert_eq!(list.ceiling(&29).map(|e| *e.key()), Some(30));
+
+        assert!(list.floor(&-1).is_none());
+        assert_eq!(list.floor(&1_000).map(|e| *e.key()), Some(90));
+        assert_eq!(list.ceiling(&-1).map(|e| *e.key()), Some(0));
+        assert!(list.ceiling(&91).is_none());
+        assert!(list.upper_bound(&90).is_none());
+
+        list.remove(&20);
+
+        assert_eq!(list.floor(&25).map(|e| *e.key()), Some(10));
     }
-}
 
-impl<'a, K, V> core::cmp::PartialEq for NodeRef<'a, K, V> {
-    fn eq(&self, other: &Self) -> bool {
-        core::ptr::eq(self.node.as_ptr(), other.node.as_ptr())
+    #[test]
+    fn test_borrowed_lookup() {
+        let list = SkipList::new();
+
+        for word in ["apple", "banana", "cherry", "date"] {
+            list.insert(word.to_string(), word.len());
+        }
+
+        assert_eq!(list.get("banana").map(|e| *e.val()), Some(6));
+        assert!(list.contains_key("cherry"));
+        assert!(!list.contains_key("fig"));
+        assert_eq!(list.floor("c").map(|e| e.key().clone()), Some("banana".to_string()));
+        assert_eq!(
+            list.range::<str, _>((Bound::Included("b"), Bound::Excluded("d")))
+                .map(|e| e.key().clone())
+                .collect::<Vec<_>>(),
+            vec!["banana", "cherry"]
+        );
+        assert!(list.remove("apple").is_some());
+        assert!(!list.contains_key("apple"));
     }
-}
 
-impl<'a, K, V> core::cmp::Eq for NodeRef<'a, K, V> {}
+    #[test]
+    fn test_comparator() {
+        use crate::comparator::Comparator;
+        use core::cmp::Ordering;
 
-#[repr(transparent)]
-struct DeallocOnDrop<K, V>(*mut Node<K, V>);
+        struct Collation {
+            case_sensitive: bool,
+        }
 
-unsafe impl<K, V> Send for DeallocOnDrop<K, V> 
-where K: Send + Sync,
-      V: Send + Sync
-{
-}
+        impl Comparator<str> for Collation {
+            fn compare(&self, a: &str, b: &str) -> Ordering {
+                match self.case_sensitive {
+                    true => a.cmp(b),
+                    false => a.to_lowercase().cmp(&b.to_lowercase()),
+                }
+            }
+        }
 
-unsafe impl<K, V> Sync for DeallocOnDrop<K, V> 
-where K: Send + Sync,
-      V: Send + Sync
-{
-}
+        impl Comparator<String> for Collation {
+            fn compare(&self, a: &String, b: &String) -> Ordering {
+                Comparator::<str>::compare(self, a, b)
+            }
+        }
 
-impl<K, V> From<*mut Node<K, V>> for DeallocOnDrop<K, V> {
-    fn from(node: *mut Node<K, V>) -> Self {
-        DeallocOnDrop(node)
+        let list = SkipList::with_comparator(Collation { case_sensitive: false });
+
+        for word in ["banana", "Apple", "cherry"] {
+            list.insert(word.to_string(), ());
+        }
+
+        assert!(list.insert("BANANA".to_string(), ()).is_some());
+        assert_eq!(list.len(), 3);
+        assert!(list.contains_key("CHERRY"));
+        assert_eq!(list.iter().map(|e| e.key().clone()).collect::<Vec<_>>(), vec!["Apple", "BANANA", "cherry"]);
+
+        let list = SkipList::with_comparator(|a: &i32, b: &i32| b.cmp(a));
+
+        for i in 0..10 {
+            list.insert(i, ());
+        }
+
+        assert_eq!(list.iter().take(3).map(|e| *e.key()).collect::<Vec<_>>(), vec![9, 8, 7]);
+        assert_eq!(list.range(6..).map(|e| *e.key()).collect::<Vec<_>>(), vec![6, 5, 4, 3, 2, 1, 0]);
+        assert_eq!(list.lower_bound(&20).map(|e| *e.key()), Some(9));
     }
-}
 
-impl<K, V> Drop for DeallocOnDrop<K, V> {
-    fn drop(&mut self) {
-        unsafe {
-            Node::drop(self.0)
+    #[test]
+    fn test_get_or_insert() {
+        let list = SkipList::new();
+
+        assert_eq!(*list.get_or_insert(1, "first").val(), "first");
+        assert_eq!(*list.get_or_insert(1, "second").val(), "first");
+        assert_eq!(*list.get_or_insert_with(1, || panic!("key is present")).val(), "first");
+        assert_eq!(*list.get_or_insert_with(2, || "third").val(), "third");
+        assert_eq!(list.len(), 2);
+
+        match list.entry(3) {
+            MapEntry::Occupied(_) => panic!("key 3 was never inserted"),
+            MapEntry::Vacant(vacant) => assert_eq!(*vacant.insert("fourth").val(), "fourth"),
         }
+
+        assert_eq!(*list.entry(3).or_insert("fifth").val(), "fourth");
+        assert_eq!(*list.entry(4).or_insert_with(|| "sixth").val(), "sixth");
+        assert_eq!(list.len(), 4);
+
+        assert_eq!(list.try_insert(4, "seventh").map_err(|e| *e.val()).err(), Some("sixth"));
+        assert_eq!(list.try_insert(5, "eighth").map(|e| *e.val()).ok(), Some("eighth"));
+        assert_eq!(list.len(), 5);
     }
-}
 
-unsafe impl<K, V> Pointer<Node<K, V>> for DeallocOnDrop<K, V> {
-    fn into_raw(self) -> *mut Node<K, V> {
-        self.0
+    #[test]
+    fn test_entry_and_modify() {
+        use core::sync::atomic::AtomicUsize;
+
+        let list: SkipList<&str, AtomicUsize> = SkipList::new();
+
+        for _ in 0..5 {
+            list.entry("hits")
+                .and_modify(|count| {
+                    count.fetch_add(1, Ordering::Relaxed);
+                })
+                .or_insert_with(|| AtomicUsize::new(1));
+        }
+
+        assert_eq!(list.get("hits").unwrap().val().load(Ordering::Relaxed), 5);
     }
 
-    unsafe fn from_raw(ptr: *mut Node<K, V>) -> Self {
-        DeallocOnDrop::from(ptr)
+    #[test]
+    fn test_sync_get_or_insert() {
+        use std::sync::Arc;
+        let list = Arc::new(SkipList::new());
+
+        let threads = (0..20)
+            .map(|thread| {
+                let list = list.clone();
+                std::thread::spawn(move || {
+                    (0..200u8)
+                        .map(|key| *list.get_or_insert(key, thread).val())
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect::<Vec<_>>();
+
+        let winners = threads
+            .into_iter()
+            .map(|thread| thread.join().unwrap())
+            .collect::<Vec<_>>();
+
+        assert_eq!(list.len(), 200);
+
+        // Every thread has to observe the same winner for each key.
+        for (key, entry) in list.iter().enumerate() {
+            assert!(winners.iter().all(|w| w[key] == *entry.val()));
+        }
     }
-}
 
-impl<K, V> core::ops::Deref for DeallocOnDrop<K, V> {
-    type Target = Node<K, V>;
+    #[test]
+    fn test_conditional_mutations() {
+        let list = SkipList::new();
 
-    fn deref(&self) -> &Self::Target {
-        unsafe { &(*self.0) }
+        list.insert(1, 10);
+        list.insert(2, 20);
+
+        assert!(list.remove_if(&1, |v| *v > 10).is_none());
+        assert_eq!(list.remove_if(&1, |v| *v == 10).map(|e| *e.val()), Some(10));
+        assert!(list.remove_if(&1, |_| true).is_none());
+
+        assert_eq!(list.compare_and_swap(2, &21, 22).err(), Some(22));
+        assert_eq!(list.compare_and_swap(2, &20, 22).map(|e| *e.val()), Ok(20));
+        assert_eq!(*list.get(&2).unwrap().val(), 22);
+
+        assert_eq!(list.replace_if(2, |v| *v < 20, 23).err(), Some(23));
+        assert_eq!(list.replace_if(2, |v| *v > 20, 23).map(|e| *e.val()), Ok(22));
+        assert_eq!(list.replace_if(3, |_| true, 30).err(), Some(30));
+
+        assert_eq!(*list.get(&2).unwrap().val(), 23);
+        assert_eq!(list.len(), 1);
     }
-}
 
-impl<K, V> core::ops::DerefMut for DeallocOnDrop<K, V> {
-    fn deref_mut(&mut self) -> &mut Self::Target {
-        unsafe {&mut (*self.0)}
+    #[test]
+    fn test_sync_compare_and_swap() {
+        use std::sync::Arc;
+        let list = Arc::new(SkipList::new());
+
+        list.insert((), 0);
+
+        let threads = (0..20)
+            .map(|_| {
+                let list = list.clone();
+                std::thread::spawn(move || {
+                    for _ in 0..100 {
+                        loop {
+                            let current = *list.get(&()).unwrap().val();
+
+                            if list.compare_and_swap((), &current, current + 1).is_ok() {
+                                break;
+                            }
+                        }
+                    }
+                })
+            })
+            .collect::<Vec<_>>();
+
+        for thread in threads {
+            thread.join().unwrap()
+        }
+
+        assert_eq!(*list.get(&()).unwrap().val(), 2_000);
     }
-}
 
-#[cfg(test)]
-mod sync_test {
-    use rand::Rng;
+    #[test]
+    fn test_sync_replace_remove() {
+        use std::sync::Arc;
+        let list = Arc::new(SkipList::new());
 
-    use super::*;
+        for i in 0..100 {
+            list.insert(i, 0);
+        }
+
+        let threads = (0..8)
+            .map(|t| {
+                let list = list.clone();
+                std::thread::spawn(move || {
+                    let mut removed = 0;
+                    for i in 0..100 {
+                        if t % 2 == 0 {
+                            for _ in 0..10 {
+                                let _ = list.replace_if(i, |_| true, t);
+                            }
+                        } else if list.remove(&i).is_some() {
+                            removed += 1;
+                        }
+                    }
+                    removed
+                })
+            })
+            .collect::<Vec<_>>();
+
+        let removed = threads
+            .into_iter()
+            .map(|thread| thread.join().unwrap())
+            .sum::<usize>();
+
+        assert_eq!(removed, 100);
+        assert!(list.is_empty());
+        assert_eq!(list.iter().count(), 0);
+    }
 
     #[test]
-    fn test_new_node_sync() {
-        let node = Node::new(100, "hello", 1);
-        let other = Node::new(100, "hello", 1);
-        unsafe { println!("node 1: {:?},", *node) };
-        unsafe { println!("node 2: {:?},", *other) };
-        let other = unsafe {
-            let node = Node::alloc(1);
-            core::ptr::write(&mut (*node).key, 100);
-            core::ptr::write(&mut (*node).val, "hello");
-            node
-        };
+    fn test_cursor() {
+        let list = SkipList::new();
 
-        unsafe { println!("node 1: {:?}, node 2: {:?}", *node, *other) };
+        for i in (0..10).map(|i| i * 2) {
+            list.insert(i, i * 10);
+        }
 
-        unsafe { assert_eq!(*node, *other) };
+        let mut cursor = list.cursor();
+
+        assert!(cursor.key().is_none());
+        assert_eq!(cursor.next().map(|e| *e.key()), Some(0));
+        assert_eq!(cursor.prev().map(|e| *e.key()), None);
+        assert_eq!(cursor.prev().map(|e| *e.key()), Some(18));
+
+        assert_eq!(cursor.seek(&5).map(|e| *e.key()), Some(6));
+        assert_eq!(cursor.value(), Some(&60));
+        assert_eq!(cursor.next().map(|e| *e.key()), Some(8));
+        assert_eq!(cursor.prev().map(|e| *e.key()), Some(6));
+
+        // The current entry is removed from under the cursor.
+        list.remove(&6);
+
+        assert_eq!(cursor.key(), Some(&6));
+        assert_eq!(cursor.next().map(|e| *e.key()), Some(8));
+
+        list.remove(&8);
+
+        assert_eq!(cursor.prev().map(|e| *e.key()), Some(4));
+
+        assert_eq!(cursor.remove_current().map(|e| *e.key()), Some(4));
+        assert!(cursor.remove_current().is_none());
+        assert!(!list.contains_key(&4));
+        assert_eq!(list.len(), 7);
+
+        assert_eq!(cursor.next().map(|e| *e.key()), Some(10));
+        assert_eq!(cursor.seek(&100).map(|e| *e.key()), None);
     }
 
-    #[test]
-    fn test_new_list_sync() {
-        let _: SkipList<'_, usize, usize> = SkipList::new();
+    #[test]
+    fn test_sync_cursor() {
+        use std::sync::Arc;
+        let list = Arc::new(SkipList::new());
+
+        for i in 0..1_000 {
+            list.insert(i, ());
+        }
+
+        let threads = (0..10)
+            .map(|t| {
+                let list = list.clone();
+                std::thread::spawn(move || {
+                    let mut cursor = list.cursor();
+                    let mut last = None;
+
+                    while let Some(&key) = cursor.next().map(|e| e.key()) {
+                        assert!(last < Some(key));
+                        last = Some(key);
+
+                        if key % 10 == t {
+                            cursor.remove_current();
+                        }
+                    }
+                })
+            })
+            .collect::<Vec<_>>();
+
+        for thread in threads {
+            thread.join().unwrap()
+        }
+
+        assert!(list.is_empty());
+        assert!(list.get_first().is_none());
     }
 
     #[test]
-    fn test_insert_sync() {
+    fn test_multi() {
         let list = SkipList::new();
-        let mut rng: u16 = rand::random();
 
-        for _ in 0..10_000 {
-            rng ^= rng << 3;
-            rng ^= rng >> 12;
-            rng ^= rng << 7;
-            list.insert(rng, "hello there!");
+        for (key, val) in [(2, "a"), (1, "b"), (2, "c"), (3, "d"), (2, "e")] {
+            list.insert_multi(key, val);
         }
-    }
-
-    #[test]
-    fn test_rand_height_sync() {
-        let mut list: SkipList<'_, i32, i32> = SkipList::new();
-        let node = Node::new_rand_height("Hello", "There!", &mut list);
 
-        assert!(!node.is_null());
-        let height = unsafe { (*node).levels.pointers.len() };
+        assert_eq!(list.len(), 5);
+        assert_eq!(list.get_all(&2).map(|e| *e.val()).collect::<Vec<_>>(), vec!["a", "c", "e"]);
+        assert_eq!(list.iter().rev().map(|e| *e.val()).collect::<Vec<_>>(), vec!["d", "e", "c", "a", "b"]);
 
-        println!("height: {}", height);
+        let mut cursor = list.cursor();
 
-        unsafe {
-            println!("{}", *node);
-        }
+        assert_eq!(cursor.seek(&3).map(|e| *e.val()), Some("d"));
+        assert_eq!(cursor.prev().map(|e| *e.val()), Some("e"));
+        assert_eq!(cursor.remove_current().map(|e| *e.val()), Some("e"));
+        assert_eq!(list.get_all(&2).map(|e| *e.val()).collect::<Vec<_>>(), vec!["a", "c"]);
 
-        unsafe {
-            let _ = Box::from_raw(node);
-        }
+        assert_eq!(list.remove_one(&2).map(|e| *e.val()), Some("a"));
+        assert_eq!(list.get(&2).map(|e| *e.val()), Some("c"));
+        assert_eq!(list.remove_all(&2), 1);
+        assert_eq!(list.get_all(&2).count(), 0);
+        assert_eq!(list.len(), 2);
     }
 
     #[test]
-    fn test_drop() {
-        struct CountOnDrop<K> {
-            key: K,
-            counter: std::sync::Arc<std::sync::atomic::AtomicUsize>,
+    fn test_multi_towers() {
+        let list = SkipList::new();
+
+        for i in 0..256 {
+            list.insert_multi(0, i);
         }
 
-        impl<K> CountOnDrop<K> {
-            fn new(key: K, counter: std::sync::Arc<std::sync::atomic::AtomicUsize>) -> Self {
-                CountOnDrop { key, counter }
-            }
+        // Duplicates are linked above the base level as well, in the order of the base level.
+        let mut towers = Vec::new();
+        let mut node = unsafe { list.head.as_ref().levels[1].load_ptr() };
 
-            fn new_none(key: K) -> Self {
-                CountOnDrop { key, counter: std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0)) }
-            }
-        }
-        impl<K: Eq> PartialEq for CountOnDrop<K> {
-            fn eq(&self, other: &Self) -> bool {
-                self.key == other.key
+        while !node.is_null() {
+            unsafe {
+                towers.push((*node).val);
+                node = (&(*node).levels)[1].load_ptr();
             }
         }
 
-        impl<K: Eq> Eq for CountOnDrop<K> {}
+        assert!(towers.len() > 1);
+        assert!(towers.windows(2).all(|w| w[0] < w[1]));
+        assert!(list.get_all(&0).map(|e| *e.val()).eq(0..256));
+    }
 
-        impl<K: Ord> PartialOrd for CountOnDrop<K> {
-            fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-                Some(self.key.cmp(&other.key))
-            }
-        }
+    #[test]
+    fn test_multi_rev() {
+        let list = SkipList::new();
 
-        impl<K: Ord> Ord for CountOnDrop<K> {
-            fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-                self.key.cmp(&other.key)
-            }
+        list.insert(0, 0);
+        for i in 0..256 {
+            list.insert_multi(1, i);
         }
+        list.insert(2, 0);
 
-        impl<K> Drop for CountOnDrop<K> {
-            fn drop(&mut self) {
-                println!("writing to counter!");
-                println!("count: {}", self.counter.load(Ordering::SeqCst));
-                self.counter.fetch_add(1, Ordering::SeqCst);
-                println!("wrote to counter");
-            }
-        }
+        assert!(list.get_all(&1).rev().map(|e| *e.val()).eq((0..256).rev()));
+        assert!(list.iter().rev().map(|e| *e.key()).eq([2].into_iter().chain([1; 256]).chain([0])));
 
-        let counter = std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        // Stepping back from a removed duplicate moves past its whole key, rather than handing
+        // out a later duplicate again.
+        let removed = list.get_all(&1).nth(100).unwrap();
+        let kept = removed.clone();
+        removed.remove();
 
-        let list = SkipList::new();
+        assert_eq!(list.prev_node(&kept).map(|e| *e.key()), Some(0));
+        assert_eq!(list.prev_run(&list.get_all(&1).nth(3).unwrap()).len(), 3);
+    }
 
-        list.insert(CountOnDrop::new(1, counter.clone()), ());
+    #[test]
+    fn test_from_sorted_iter() {
+        let list = SkipList::<u32, u32>::from_sorted_iter((0..1_000).map(|i| (i * 2, i)));
 
-        list.remove(&CountOnDrop::new_none(1));
+        assert_eq!(list.len(), 1_000);
+        assert!(list.iter().map(|e| *e.key()).eq((0..1_000).map(|i| i * 2)));
+        assert_eq!(list.get(&500).map(|e| *e.val()), Some(250));
 
-        // assert_eq!(counter.load(Ordering::SeqCst), 1);
+        list.insert(1, 1);
+        assert_eq!(list.remove(&0).map(|e| *e.val()), Some(0));
+        assert_eq!(list.get_first().map(|e| *e.key()), Some(1));
+        assert_eq!(list.get_last().map(|e| *e.key()), Some(1_998));
+        assert_eq!(list.len(), 1_000);
+    }
 
-        list.insert(CountOnDrop::new(1, counter.clone()), ());
+    #[test]
+    fn test_from_single_threaded() {
+        let mut local = crate::internal::skiplist::SkipList::new();
 
-        list.insert(CountOnDrop::new(1, counter.clone()), ());
+        for i in 0..1_000 {
+            local.insert(i, i.to_string());
+        }
+        local.remove_nth(0);
 
-        println!("length: {}", list.len());
+        let list = SkipList::from(local);
 
-        list.garbage.domain.eager_reclaim();
+        assert_eq!(list.len(), 999);
+        assert!(list.iter().map(|e| *e.key()).eq(1..1_000));
+        assert_eq!(list.get(&500).map(|e| e.val().clone()), Some("500".to_string()));
 
-        core::sync::atomic::fence(Ordering::SeqCst);
+        assert_eq!(list.remove(&1).map(|e| *e.key()), Some(1));
+        list.insert(0, "0".to_string());
+        assert_eq!(list.get_first().map(|e| *e.key()), Some(0));
+        assert_eq!(list.len(), 999);
+    }
 
-        assert_eq!(counter.load(Ordering::SeqCst), 2);
+    #[test]
+    fn test_split_off() {
+        let mut list = SkipList::<u32, u32>::from_sorted_iter((0..1_000).map(|i| (i, i)));
+
+        list.remove(&700);
+        let mut other = list.split_off(&600);
+
+        assert_eq!(list.len(), 600);
+        assert_eq!(other.len(), 399);
+        assert!(list.iter().map(|e| *e.key()).eq(0..600));
+        assert!(other.iter().map(|e| *e.key()).eq((600..1_000).filter(|i| *i != 700)));
+        assert_eq!(list.get_last().map(|e| *e.key()), Some(599));
+
+        other.insert(700, 700);
+        list.append(&mut other);
+
+        assert!(other.is_empty());
+        assert!(other.get_first().is_none());
+        assert_eq!(list.len(), 1_000);
+        assert!(list.iter().map(|e| *e.key()).eq(0..1_000));
+
+        // overlapping lists are merged entry by entry
+        let mut other = SkipList::new();
+        other.insert(1, 0);
+        other.insert(1_000, 0);
+        list.append(&mut other);
+
+        assert!(other.is_empty());
+        assert_eq!(list.len(), 1_002);
+        assert_eq!(list.get(&1).map(|e| *e.val()), Some(1));
+        assert_eq!(list.get_last().map(|e| *e.key()), Some(1_000));
+    }
 
-        drop(list);
+    #[test]
+    fn test_append_duplicates() {
+        let mut list = SkipList::<u32, u32>::new();
+        let mut other = SkipList::<u32, u32>::new();
+
+        for i in 0..10 {
+            list.insert_multi(i, 0);
+            list.insert_multi(i, 1);
+            other.insert_multi(i * 2, 2);
+            other.insert_multi(i * 2, 3);
+        }
 
-        assert_eq!(counter.load(Ordering::SeqCst), 3);
+        list.append(&mut other);
+
+        assert!(other.is_empty());
+        assert_eq!(list.len(), 40);
+        assert_eq!(list.iter().count(), 40);
+        assert_eq!(
+            list.get_all(&4).map(|e| *e.val()).collect::<Vec<_>>(),
+            vec![0, 1, 2, 3]
+        );
+        assert_eq!(
+            list.get_all(&14).map(|e| *e.val()).collect::<Vec<_>>(),
+            vec![2, 3]
+        );
 
+        // Equal keys at the boundary are linked behind ours as well.
+        let mut other = SkipList::new();
+        other.insert_multi(18, 4);
+        other.insert_multi(19, 4);
+        list.append(&mut other);
+
+        assert_eq!(list.len(), 42);
+        assert_eq!(
+            list.get_all(&18).map(|e| *e.val()).collect::<Vec<_>>(),
+            vec![2, 3, 4]
+        );
     }
 
     #[test]
-    fn test_insert_verbose_sync() {
-        let list = SkipList::new();
+    fn test_extract_if() {
+        let list = SkipList::<i32, i32>::from_sorted_iter((0..100).map(|i| (i, i % 3)));
 
-        list.insert(1, 1);
-
-        list.iter().for_each(|n| println!("k: {},", n.key()));
+        let extracted = list.extract_if(|_, v| *v == 0).map(|e| *e.key()).collect::<Vec<_>>();
 
-        list.insert(2, 2);
+        assert_eq!(extracted, (0..100).step_by(3).collect::<Vec<_>>());
+        assert_eq!(list.len(), 66);
+        assert!(list.get(&3).is_none());
 
-        list.iter().for_each(|n| println!("k: {},", n.key()));
+        list.retain(|k, _| k % 2 == 0);
 
-        list.insert(5, 3);
+        assert_eq!(list.len(), 33);
+        assert!(list.iter().all(|e| e.key() % 2 == 0 && e.key() % 3 != 0));
 
-        list.iter().for_each(|n| println!("k: {},", n.key()));
+        // Dropping the iterator early keeps the entries it has not visited.
+        assert_eq!(list.extract_if(|_, _| true).take(3).count(), 3);
+        assert_eq!(list.len(), 30);
+        assert_eq!(list.iter().count(), 30);
     }
 
     #[test]
-    fn test_remove() {
-        let list = SkipList::new();
-        let mut rng: u16 = rand::random();
+    fn test_sync_retain() {
+        use std::sync::Arc;
+        let list = Arc::new(SkipList::new());
 
-        for _ in 0..10_000 {
-            rng ^= rng << 3;
-            rng ^= rng >> 12;
-            rng ^= rng << 7;
-            list.insert(rng, "hello there!");
-        }
-        for _ in 0..10_000 {
-            rng ^= rng << 3;
-            rng ^= rng >> 12;
-            rng ^= rng << 7;
-            list.remove(&rng);
-        }
-    }
+        let threads = (0..10)
+            .map(|t| {
+                let list = list.clone();
+                std::thread::spawn(move || {
+                    for i in 0..1_000 {
+                        list.insert(i * 10 + t, t);
 
-    #[test]
-    fn test_verbose_remove() {
-        let list = SkipList::new();
+                        if i % 100 == 0 {
+                            list.retain(|_, v| v % 2 == 0);
+                        }
+                    }
+                })
+            })
+            .collect::<Vec<_>>();
 
-        list.insert(1, 1);
-        list.insert(2, 2);
-        list.insert(2, 2);
-        list.insert(5, 3);
+        for thread in threads {
+            thread.join().unwrap()
+        }
 
-        list.iter().for_each(|n| println!("k: {},", n.key()));
+        list.retain(|_, v| v % 2 == 0);
 
-        assert!(list.remove(&1).is_some());
+        assert_eq!(list.len(), 5_000);
+        assert_eq!(list.iter().count(), 5_000);
+        assert!(list.iter().all(|e| e.val() % 2 == 0));
+    }
 
-        list.iter().for_each(|n| println!("k: {},", n.key()));
+    #[test]
+    fn test_clear() {
+        let list = SkipList::<u32, u32>::from_sorted_iter((0..1_000).map(|i| (i, i)));
+        let entry = list.get(&500).unwrap();
 
-        println!("removing 6");
-        assert!(list.remove(&6).is_none());
-        println!("removing 1");
-        assert!(list.remove(&1).is_none());
-        println!("removing 5");
-        assert!(list.remove(&5).is_some());
-        println!("removing 2");
-        assert!(list.remove(&2).is_some());
+        list.clear();
 
-        list.iter().for_each(|n| println!("k: {},", n.key()));
+        assert!(list.is_empty());
+        assert!(list.get_first().is_none());
+        assert!(list.get_last().is_none());
+        assert_eq!(list.iter().count(), 0);
+        assert_eq!(*entry.val(), 500);
 
-        assert_eq!(list.len(), 0);
+        list.insert(1, 1);
+        assert_eq!(list.len(), 1);
+        assert_eq!(list.get(&1).map(|e| *e.val()), Some(1));
     }
 
     #[test]
-    fn test_find_removed() {
-        let list = SkipList::new();
+    fn test_sync_clear() {
+        use std::sync::Arc;
+        let list = Arc::new(SkipList::new());
 
-        list.insert(3, ());
+        let threads = (0..10)
+            .map(|t| {
+                let list = list.clone();
+                std::thread::spawn(move || {
+                    for i in 0..1_000 {
+                        list.insert(i * 10 + t, t);
 
-        list.insert(4, ());
+                        if let Some(entry) = list.get_first() {
+                            assert_eq!(*entry.key() % 10, *entry.val());
+                        }
 
-        list.insert(5, ());
+                        if i % 3 == 0 {
+                            list.remove(&(i * 10 + t));
+                        }
 
-        assert!(list.find(&3, false).target.is_some());
-        assert!(list.find(&4, false).target.is_some());
+                        if t == 0 && i % 100 == 0 {
+                            list.clear();
+                        }
+                    }
+                })
+            })
+            .collect::<Vec<_>>();
 
-        // manually get reference to the nodes
-        let node_3 = unsafe { &mut (*(*list.head.as_ptr()).levels[0].load_ptr()) };
-        let node_4 =
-            unsafe { &mut (*(*(*list.head.as_ptr()).levels[0].load_ptr()).levels[0].load_ptr()) };
-        let node_5 = unsafe {
-            &mut (*(*(*(*list.head.as_ptr()).levels[0].load_ptr()).levels[0].load_ptr()).levels[0]
-                .load_ptr())
-        };
+        for thread in threads {
+            thread.join().unwrap()
+        }
 
-        // make sure it is the right node
-        assert_eq!(node_3.key, 3);
-        println!("{:?}", node_3);
-        assert_eq!(node_4.key, 4);
-        println!("{:?}", node_4);
-        assert_eq!(node_5.key, 5);
-        println!("{:?}", node_5);
+        assert_eq!(list.len(), list.iter().count());
 
-        // remove the node logically
-        let _ = node_4.set_removed();
+        list.clear();
 
-        assert!(list.find(&4, false).target.is_none());
+        assert!(list.is_empty());
+        assert!(list.get_first().is_none());
+    }
 
-        println!("{:?}", list.find(&3, false));
+    #[test]
+    fn test_remove_owned() {
+        let list = SkipList::new();
 
-        assert!(!node_3.removed());
+        for i in 0..10 {
+            list.insert(i, i.to_string());
+        }
 
-        assert!(list.remove(&4).is_none());
+        assert_eq!(list.remove_owned(&3).unwrap().ok(), Some((3, "3".to_string())));
+        assert!(list.remove_owned(&3).is_none());
+        assert_eq!(list.len(), 9);
+        assert!(list.get(&3).is_none());
 
-        // remove the node logically
-        node_4.height_and_removed.store(
-            node_4.height_and_removed.load(Ordering::SeqCst) & (usize::MAX >> 1),
-            Ordering::SeqCst,
-        );
+        // Should a reader still hold the node, it is handed back instead of waited for.
+        let entry = list.get(&5).unwrap();
 
-        assert!(!node_4.removed());
+        let Some(Err(removed)) = list.remove_owned(&5) else {
+            panic!("a held node should be handed back");
+        };
 
-        assert!(list.remove(&4).is_some());
+        assert!(removed.is(&entry));
+        assert_eq!(removed.val(), "5");
+        assert!(list.get(&5).is_none());
+        assert_eq!(list.len(), 8);
+
+        drop(entry);
+        drop(removed);
+
+        // Once the reader is gone, the same key is handed out by value again.
+        list.insert(5, "6".to_string());
+        assert_eq!(list.remove_owned(&5).unwrap().ok(), Some((5, "6".to_string())));
+
+        assert_eq!(list.remove_first_owned().unwrap().ok(), Some((0, "0".to_string())));
+        assert_eq!(list.remove_last_owned().unwrap().ok(), Some((9, "9".to_string())));
     }
 
     #[test]
-    fn test_sync_remove() {
+    fn test_sync_remove_owned() {
         use std::sync::Arc;
         let list = Arc::new(SkipList::new());
-        let mut rng = rand::thread_rng();
 
-        for _ in 0..10_000 {
-            list.insert(rng.gen::<u16>(), ());
+        for i in 0..1_000 {
+            list.insert(i, Box::new(i));
         }
-        let threads = (0..20)
+
+        let threads = (0..10)
             .map(|_| {
                 let list = list.clone();
                 std::thread::spawn(move || {
-                    let mut rng = rand::thread_rng();
-                    for _ in 0..1_000 {
-                        let target = &rng.gen::<u16>();
-                        list.remove(&target);
+                    let mut removed = Vec::new();
+                    for i in 0..1_000 {
+                        match list.remove_owned(&i) {
+                            Some(Ok((key, val))) => {
+                                assert_eq!(key, *val);
+                                removed.push(key);
+                            }
+                            Some(Err(entry)) => removed.push(*entry.key()),
+                            None => {}
+                        }
                     }
+                    removed
                 })
             })
             .collect::<Vec<_>>();
 
-        for thread in threads {
-            thread.join().unwrap()
-        }
+        let mut removed = threads
+            .into_iter()
+            .flat_map(|thread| thread.join().unwrap())
+            .collect::<Vec<_>>();
+        removed.sort();
 
-        list.iter().for_each(|e| println!("key: {}", e.key));
+        assert_eq!(removed, (0..1_000).collect::<Vec<_>>());
+        assert!(list.is_empty());
     }
 
     #[test]
-    fn test_sync_insert() {
+    fn test_sync_remove_owned_with_readers() {
+        use std::sync::atomic::AtomicBool;
         use std::sync::Arc;
         let list = Arc::new(SkipList::new());
+        let done = Arc::new(AtomicBool::new(false));
 
-        let threads = (0..20)
+        for i in 0..1_000 {
+            list.insert(i, Box::new(i));
+        }
+
+        let readers = (0..4)
             .map(|_| {
                 let list = list.clone();
+                let done = done.clone();
                 std::thread::spawn(move || {
-                    let mut rng = rand::thread_rng();
-                    for _ in 0..1_000 {
-                        let target = rng.gen::<u8>();
-
-                        list.insert(target, ());
+                    while !done.load(Ordering::Relaxed) {
+                        for entry in list.iter() {
+                            assert_eq!(*entry.key(), **entry.val());
+                        }
                     }
                 })
             })
             .collect::<Vec<_>>();
 
-        for thread in threads {
-            thread.join().unwrap()
+        let mut taken = 0;
+        while let Some(removed) = list.remove_first_owned() {
+            match removed {
+                Ok((key, val)) => assert_eq!(key, *val),
+                Err(entry) => assert_eq!(*entry.key(), **entry.val()),
+            }
+            taken += 1;
         }
 
-        list.iter().for_each(|e| println!("key: {}", e.key));
+        done.store(true, Ordering::Relaxed);
+        readers.into_iter().for_each(|r| r.join().unwrap());
+
+        assert_eq!(taken, 1_000);
+        assert!(list.is_empty());
     }
 
     #[test]
-    fn test_sync_inmove() {
+    fn test_sync_multi() {
         use std::sync::Arc;
         let list = Arc::new(SkipList::new());
 
-        let threads = (0..20)
-            .map(|_| {
+        let threads = (0..10)
+            .map(|t| {
                 let list = list.clone();
                 std::thread::spawn(move || {
-                    let mut rng = rand::thread_rng();
-                    for _ in 0..5_000 {
-                        let target = rng.gen::<u8>();
-                        if rng.gen::<u8>() % 5 == 0 {
-                            list.remove(&target);
-                        } else {
-                            list.insert(target, ());
-                        }
+                    for i in 0..100 {
+                        list.insert_multi(i % 10, (t, i));
+                    }
+
+                    for i in 0..5 {
+                        list.remove_one(&i);
                     }
                 })
             })
@@ -1060,7 +3224,23 @@ mod sync_test {
             thread.join().unwrap()
         }
 
-        list.iter().for_each(|e| println!("key: {}", e.key));
+        assert_eq!(list.len(), 950);
+
+        for key in 0..10 {
+            let values = list.get_all(&key).map(|e| *e.val()).collect::<Vec<_>>();
+
+            assert_eq!(values.len(), if key < 5 { 90 } else { 100 });
+
+            // Values of each thread keep their insertion order.
+            for t in 0..10 {
+                let own = values.iter().filter(|(o, _)| *o == t).map(|(_, i)| *i).collect::<Vec<_>>();
+                assert!(own.windows(2).all(|w| w[0] < w[1]));
+            }
+        }
+
+        assert_eq!(list.remove_all(&0), 90);
+        assert_eq!(list.len(), 860);
+        assert_eq!(list.iter().count(), 860);
     }
 
     #[test]
@@ -1093,7 +3273,7 @@ mod sync_test {
             thread.join().unwrap()
         }
 
-        let list = Arc::<SkipList<'_, u8, ()>>::try_unwrap(list).unwrap();
+        let list = Arc::<SkipList<u8, ()>>::try_unwrap(list).unwrap();
 
         list.into_iter().for_each(|(k, _)| println!("key: {}", k))
     }
@@ -43,6 +43,16 @@ impl<T> MaybeTagged<T> {
         self.store_composed(ptr, 0);
     }
 
+    pub(crate) fn swap_ptr(&self, ptr: *mut T) -> (*mut T, usize) {
+        let raw = unsafe {
+            self.0
+                .as_std()
+                .swap(Self::compose_raw(ptr, 0), std::sync::atomic::Ordering::SeqCst)
+        };
+
+        Self::decompose_raw(raw)
+    }
+
     pub(crate) fn compare_exchange(
         &self,
         expected: *mut T,
@@ -71,23 +81,6 @@ impl<T> MaybeTagged<T> {
         }
     }
 
-    pub(crate) fn tag(&self, tag: usize) {
-        let (mut old_ptr, mut old_tag) = self.load_decomposed();
-
-        while let Err((other_ptr, other_tag)) =
-            self.compare_exchange_with_tag(old_ptr, old_tag, old_ptr, tag)
-        {
-            (old_ptr, old_tag) = (other_ptr, other_tag);
-        }
-    }
-
-    pub(crate) fn try_tag(&self, expected: *mut T, tag: usize) -> Result<*mut T, *mut T> {
-        let old_tag = self.load_tag();
-        self.compare_exchange_with_tag(expected, old_tag, expected, tag)
-            .map(|s| s.0)
-            .map_err(|e| e.0)
-    }
-
     pub(crate) fn compare_exchange_tag(&self, e_tag: usize, tag: usize) -> Result<usize, usize> {
         let mut ptr = self.load_ptr();
         while let Err((other_ptr, other_tag)) = self.compare_exchange_with_tag(ptr, e_tag, ptr, tag)
@@ -109,10 +102,6 @@ impl<T> MaybeTagged<T> {
     pub(crate) fn as_std(&self) -> &core::sync::atomic::AtomicPtr<T> {
         unsafe { self.0.as_std() }
     }
-
-    pub(crate) fn as_hpz(&self) -> &AtomicPtr<T> {
-        &self.0
-    }
 }
 
 const fn align<T>() -> usize {
@@ -130,28 +119,50 @@ fn usize_to_ptr_with_provenance<T>(addr: usize, prov: *mut T) -> *mut T {
 
 impl<'a, K, V> NodeRef<'a, K, V> {
     pub(crate) fn from_maybe_tagged(maybe_tagged: &MaybeTagged<Node<K, V>>) -> Option<Self> {
+        Self::protect(maybe_tagged).0
+    }
+
+    /// Protects the [Node](Node) stored at `maybe_tagged`, but only if the level it is read from
+    /// is not tagged.
+    ///
+    /// A tagged level belongs to a `Node` that is being unlinked, so the pointer stored in it
+    /// may be stale and its target may already have been retired. An untagged level, on the
+    /// other hand, guarantees that its target was still linked at the time it was protected.
+    pub(crate) fn from_untagged(
+        maybe_tagged: &MaybeTagged<Node<K, V>>,
+    ) -> Result<Option<Self>, ()> {
+        match Self::protect(maybe_tagged) {
+            (next, 0) => Ok(next),
+            _ => Err(()),
+        }
+    }
+
+    fn protect(maybe_tagged: &MaybeTagged<Node<K, V>>) -> (Option<Self>, usize) {
         let mut _hazard = HazardPointer::new();
-        let mut ptr = maybe_tagged.load_ptr();
+        let (mut ptr, mut tag) = maybe_tagged.load_decomposed();
 
         _hazard.protect_raw(ptr);
 
-        let mut v_ptr = maybe_tagged.load_ptr();
+        let (mut v_ptr, mut v_tag) = maybe_tagged.load_decomposed();
 
-        while !core::ptr::eq(ptr, v_ptr) {
-            ptr = v_ptr;
+        while !core::ptr::eq(ptr, v_ptr) || tag != v_tag {
+            (ptr, tag) = (v_ptr, v_tag);
             _hazard.protect_raw(ptr);
 
-            v_ptr = maybe_tagged.load_ptr();
+            (v_ptr, v_tag) = maybe_tagged.load_decomposed();
         }
 
         if ptr.is_null() {
-            None
+            (None, tag)
         } else {
             unsafe {
-                Some(NodeRef {
-                    node: core::ptr::NonNull::new_unchecked(ptr),
-                    _hazard,
-                })
+                (
+                    Some(NodeRef {
+                        node: core::ptr::NonNull::new_unchecked(ptr),
+                        _hazard,
+                    }),
+                    tag,
+                )
             }
         }
     }
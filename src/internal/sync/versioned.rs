@@ -0,0 +1,568 @@
+use core::cmp::Ordering as CmpOrdering;
+use core::sync::atomic::{AtomicU64, Ordering};
+
+use super::{Entry, SkipList};
+use crate::comparator::{Comparator, OrdComparator};
+
+/// A key of a [VersionedSkipList](VersionedSkipList), together with the sequence number of the
+/// write that produced it.
+pub(crate) struct Version<K> {
+    key: K,
+    seq: u64,
+}
+
+/// Orders the versions of a key from the newest to the oldest, and keys by `C`.
+pub(crate) struct VersionComparator<C>(C);
+
+impl<K, C> Comparator<Version<K>> for VersionComparator<C>
+where
+    C: Comparator<K>,
+{
+    fn compare(&self, a: &Version<K>, b: &Version<K>) -> CmpOrdering {
+        self.0
+            .compare(&a.key, &b.key)
+            .then_with(|| b.seq.cmp(&a.seq))
+    }
+}
+
+type VersionEntry<'a, K, V> = Entry<'a, Version<K>, Option<V>>;
+
+/// A concurrent map built on a [SkipList](SkipList) that keeps multiple versions of each key.
+///
+/// Every write is assigned a monotonically increasing sequence number, and removals leave a
+/// tombstone behind. A [Snapshot](Snapshot) reads the map exactly as it was after the writes up
+/// to its sequence number, regardless of what other threads write in the meantime.
+///
+/// Versions that no snapshot can read anymore are collected whenever their key is written, or by
+/// calling [collect_garbage](VersionedSkipList::collect_garbage).
+pub struct VersionedSkipList<K, V, C = OrdComparator> {
+    list: SkipList<Version<K>, Option<V>, VersionComparator<C>>,
+    /// The sequence number handed to the last write.
+    last_seq: AtomicU64,
+    /// All writes up to this sequence number have been applied. This only caches the latest
+    /// sequence number computed by [seq](VersionedSkipList::seq), so that it never goes back.
+    committed: AtomicU64,
+    /// A lower bound on the sequence number of every write in flight, registered before the write
+    /// is handed its sequence number and removed once it has been applied.
+    pending: SkipList<u64, ()>,
+    /// The sequence numbers of all live snapshots.
+    snapshots: SkipList<u64, ()>,
+    /// The latest sequence number garbage was collected at. Versions only visible to snapshots
+    /// taken before it, which had not been registered yet, may have been collected.
+    horizon: AtomicU64,
+}
+
+impl<K, V> VersionedSkipList<K, V>
+where
+    K: Ord + Send + Sync,
+    V: Send + Sync,
+{
+    pub fn new() -> Self {
+        Self::with_comparator(OrdComparator)
+    }
+}
+
+impl<K, V> Default for VersionedSkipList<K, V>
+where
+    K: Ord + Send + Sync,
+    V: Send + Sync,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K, V, C> VersionedSkipList<K, V, C>
+where
+    K: Send + Sync,
+    V: Send + Sync,
+    C: Comparator<K>,
+{
+    /// Creates an empty map whose keys are ordered by `comparator`.
+    pub fn with_comparator(comparator: C) -> Self {
+        VersionedSkipList {
+            list: SkipList::with_comparator(VersionComparator(comparator)),
+            last_seq: AtomicU64::new(0),
+            committed: AtomicU64::new(0),
+            pending: SkipList::new(),
+            snapshots: SkipList::new(),
+            horizon: AtomicU64::new(0),
+        }
+    }
+
+    /// Inserts `val` for `key` and returns the sequence number of the write.
+    pub fn insert(&self, key: K, val: V) -> u64 {
+        self.write(key, Some(val))
+    }
+
+    /// Removes `key` by writing a tombstone for it, and returns the sequence number of the write.
+    pub fn remove(&self, key: K) -> u64 {
+        self.write(key, None)
+    }
+
+    fn write(&self, key: K, val: Option<V>) -> u64 {
+        // We register before taking our sequence number, so a reader that sees it handed out also
+        // sees that we are in flight. The guard commits the write once it has been applied, or
+        // aborts it should we panic before, so that later writes become visible either way.
+        let guard = PendingWrite {
+            pending: &self.pending,
+            registration: self
+                .pending
+                .insert_multi(self.last_seq.load(Ordering::SeqCst) + 1, ()),
+        };
+
+        let seq = self.last_seq.fetch_add(1, Ordering::SeqCst) + 1;
+
+        let entry = self.list.insert_multi(Version { key, seq }, val);
+
+        drop(guard);
+
+        let first = self.find(&entry.key().key, u64::MAX);
+        self.collect(first, |k| self.compare(k, &entry.key().key).is_ne());
+
+        seq
+    }
+
+    /// Returns the newest value of `key`, whether or not its write has been committed yet.
+    pub fn get<'a>(&'a self, key: &K) -> Option<VersionedEntry<'a, K, V>> {
+        VersionedEntry::visible(self.find(key, u64::MAX)?)
+    }
+
+    /// Returns the sequence number up to which all writes have been committed.
+    ///
+    /// Writes commit independently of each other, so a write is only visible here once all
+    /// earlier writes have been committed as well.
+    pub fn seq(&self) -> u64 {
+        let last = self.last_seq.load(Ordering::SeqCst);
+
+        let seq = match self.pending.get_first() {
+            Some(pending) => last.min(*pending.key() - 1),
+            None => last,
+        };
+
+        self.committed.fetch_max(seq, Ordering::SeqCst).max(seq)
+    }
+
+    /// Takes a [Snapshot](Snapshot) of all writes committed so far.
+    pub fn snapshot<'a>(&'a self) -> Snapshot<'a, K, V, C> {
+        loop {
+            let seq = self.seq();
+            let registration = self.snapshots.insert_multi(seq, ());
+
+            // Should versions our snapshot needs have been collected before we were registered, we
+            // have to start over at a later sequence number.
+            if self.horizon.load(Ordering::SeqCst) <= seq {
+                return Snapshot {
+                    list: self,
+                    seq,
+                    registration,
+                };
+            }
+
+            self.snapshots.remove_entry(&registration);
+        }
+    }
+
+    /// Removes all versions that can not be read by any [Snapshot](Snapshot) anymore. This takes
+    /// a pass over the whole list.
+    pub fn collect_garbage(&self) {
+        self.collect(self.list.get_first(), |_| false);
+    }
+
+    fn compare(&self, key: &K, other: &K) -> CmpOrdering {
+        self.list.comparator.0.compare(key, other)
+    }
+
+    /// Finds the newest version of `key` that is not newer than `seq`.
+    fn find<'a>(&'a self, key: &K, seq: u64) -> Option<VersionEntry<'a, K, V>> {
+        let target = self.list.find_by(|v| match self.compare(&v.key, key) {
+            CmpOrdering::Less => true,
+            CmpOrdering::Equal => v.seq > seq,
+            CmpOrdering::Greater => false,
+        });
+        let target: VersionEntry<'a, K, V> = self.list.to_entry(target.target?);
+
+        if self.compare(&target.key().key, key).is_ne() {
+            return None;
+        }
+
+        Some(target)
+    }
+
+    /// Removes the versions from `next` onward that can not be read by any snapshot, stopping at
+    /// the first version of a key for which `stop` returns `true`.
+    ///
+    /// A version is read by a snapshot if it is the newest version of its key that is not newer
+    /// than the snapshot. Besides live snapshots, we keep the versions of snapshots that may still
+    /// be taken, which are those from the currently committed sequence number onward.
+    fn collect<'a, F>(&'a self, mut next: Option<VersionEntry<'a, K, V>>, stop: F)
+    where
+        F: Fn(&K) -> bool,
+    {
+        let committed = self.seq();
+
+        // Snapshots that have not been registered yet are made to start after `committed`.
+        self.horizon.fetch_max(committed, Ordering::SeqCst);
+
+        let is_read = |seq: u64, newer: u64| {
+            seq > committed
+                || committed < newer
+                || self
+                    .snapshots
+                    .lower_bound(&seq)
+                    .is_some_and(|s| *s.key() < newer)
+        };
+
+        // The version preceding `entry`, which is newer if it is of the same key.
+        let mut prev: Option<VersionEntry<'a, K, V>> = None;
+        // The oldest version of the current key that we keep.
+        let mut oldest: Option<VersionEntry<'a, K, V>> = None;
+
+        while let Some(entry) = next {
+            if stop(&entry.key().key) {
+                break;
+            }
+
+            next = self.list.next_node(&entry);
+
+            let newer = prev
+                .as_ref()
+                .filter(|p| self.compare(&p.key().key, &entry.key().key).is_eq())
+                .map(|p| p.key().seq);
+
+            match newer {
+                Some(newer) if !is_read(entry.key().seq, newer) => {
+                    self.list.remove_entry(&entry);
+                }
+                Some(_) => oldest = Some(entry.clone()),
+                None => {
+                    if let Some(oldest) = oldest.replace(entry.clone()) {
+                        self.remove_tombstone(oldest);
+                    }
+                }
+            }
+
+            prev = Some(entry);
+        }
+
+        if let Some(oldest) = oldest {
+            self.remove_tombstone(oldest);
+        }
+    }
+
+    /// Removes `entry` if it is a tombstone. Once all older versions of its key are gone, a
+    /// tombstone hides nothing anymore.
+    fn remove_tombstone<'a>(&'a self, entry: VersionEntry<'a, K, V>) {
+        if entry.val().is_none() {
+            self.list.remove_entry(&entry);
+        }
+    }
+}
+
+/// Registers a write in flight with a [VersionedSkipList](VersionedSkipList) for as long as it
+/// lives.
+struct PendingWrite<'a> {
+    pending: &'a SkipList<u64, ()>,
+    registration: Entry<'a, u64, ()>,
+}
+
+impl<'a> Drop for PendingWrite<'a> {
+    fn drop(&mut self) {
+        self.pending.remove_entry(&self.registration);
+    }
+}
+
+/// A consistent, read-only view of a [VersionedSkipList](VersionedSkipList) at a fixed sequence
+/// number. Versions the snapshot can read are kept for as long as it is alive.
+pub struct Snapshot<'a, K, V, C = OrdComparator>
+where
+    K: Send + Sync,
+    V: Send + Sync,
+    C: Comparator<K>,
+{
+    list: &'a VersionedSkipList<K, V, C>,
+    seq: u64,
+    registration: Entry<'a, u64, ()>,
+}
+
+impl<'a, K, V, C> Snapshot<'a, K, V, C>
+where
+    K: Send + Sync,
+    V: Send + Sync,
+    C: Comparator<K>,
+{
+    /// Returns the sequence number of the last write the snapshot sees.
+    pub fn seq(&self) -> u64 {
+        self.seq
+    }
+
+    pub fn get(&self, key: &K) -> Option<VersionedEntry<'a, K, V>> {
+        VersionedEntry::visible(self.list.find(key, self.seq)?)
+    }
+
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.get(key).is_some()
+    }
+
+    /// Returns an iterator over the entries as of the snapshot, in the order of their keys.
+    pub fn iter(&self) -> SnapshotIter<'a, K, V, C> {
+        SnapshotIter {
+            list: self.list,
+            seq: self.seq,
+            next: self.list.list.get_first(),
+            last: None,
+        }
+    }
+}
+
+impl<'a, K, V, C> Drop for Snapshot<'a, K, V, C>
+where
+    K: Send + Sync,
+    V: Send + Sync,
+    C: Comparator<K>,
+{
+    fn drop(&mut self) {
+        self.list.snapshots.remove_entry(&self.registration);
+    }
+}
+
+pub struct SnapshotIter<'a, K, V, C = OrdComparator>
+where
+    K: Send + Sync,
+    V: Send + Sync,
+    C: Comparator<K>,
+{
+    list: &'a VersionedSkipList<K, V, C>,
+    seq: u64,
+    next: Option<VersionEntry<'a, K, V>>,
+    /// The version of the previous key, whose older versions we skip.
+    last: Option<VersionEntry<'a, K, V>>,
+}
+
+impl<'a, K, V, C> Iterator for SnapshotIter<'a, K, V, C>
+where
+    K: Send + Sync,
+    V: Send + Sync,
+    C: Comparator<K>,
+{
+    type Item = VersionedEntry<'a, K, V>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let entry = self.next.take()?;
+            self.next = self.list.list.next_node(&entry);
+
+            if entry.key().seq > self.seq {
+                continue;
+            }
+
+            if let Some(last) = self.last.as_ref() {
+                if self.list.compare(&last.key().key, &entry.key().key).is_eq() {
+                    continue;
+                }
+            }
+
+            self.last = Some(entry.clone());
+
+            if let Some(entry) = VersionedEntry::visible(entry) {
+                return Some(entry);
+            }
+        }
+    }
+}
+
+/// A version of a key in a [VersionedSkipList](VersionedSkipList) that holds a value.
+pub struct VersionedEntry<'a, K, V> {
+    entry: VersionEntry<'a, K, V>,
+}
+
+impl<'a, K, V> VersionedEntry<'a, K, V> {
+    /// Returns `None` if `entry` is a tombstone.
+    fn visible(entry: VersionEntry<'a, K, V>) -> Option<Self> {
+        entry.val().as_ref()?;
+
+        Some(VersionedEntry { entry })
+    }
+
+    pub fn key(&self) -> &K {
+        &self.entry.key().key
+    }
+
+    pub fn val(&self) -> &V {
+        match self.entry.val() {
+            Some(val) => val,
+            None => unreachable!("tombstones are never handed out"),
+        }
+    }
+
+    /// Returns the sequence number of the write that produced this version.
+    pub fn seq(&self) -> u64 {
+        self.entry.key().seq
+    }
+}
+
+#[cfg(test)]
+mod versioned_test {
+    use super::*;
+
+    #[test]
+    fn test_snapshot() {
+        let list = VersionedSkipList::new();
+
+        list.insert(1, "a");
+        list.insert(2, "b");
+
+        let snapshot = list.snapshot();
+
+        list.insert(1, "c");
+        list.remove(2);
+        list.insert(3, "d");
+
+        assert_eq!(snapshot.seq(), 2);
+        assert_eq!(snapshot.get(&1).map(|e| *e.val()), Some("a"));
+        assert_eq!(snapshot.get(&2).map(|e| e.seq()), Some(2));
+        assert!(!snapshot.contains_key(&3));
+        assert_eq!(
+            snapshot
+                .iter()
+                .map(|e| (*e.key(), *e.val()))
+                .collect::<Vec<_>>(),
+            vec![(1, "a"), (2, "b")]
+        );
+
+        assert_eq!(list.get(&1).map(|e| *e.val()), Some("c"));
+        assert!(list.get(&2).is_none());
+        assert_eq!(
+            list.snapshot().iter().map(|e| *e.key()).collect::<Vec<_>>(),
+            vec![1, 3]
+        );
+
+        // The versions read by `snapshot` are kept until it is dropped.
+        list.collect_garbage();
+        assert_eq!(list.list.len(), 5);
+
+        drop(snapshot);
+        list.collect_garbage();
+
+        assert_eq!(list.list.len(), 2);
+        assert_eq!(list.get(&1).map(|e| *e.val()), Some("c"));
+        assert_eq!(list.snapshot().iter().count(), 2);
+    }
+
+    #[test]
+    fn test_collect_on_write() {
+        let list = VersionedSkipList::new();
+
+        for i in 0..100 {
+            list.insert(0, i);
+        }
+
+        assert_eq!(list.list.len(), 1);
+
+        let snapshot = list.snapshot();
+
+        for i in 0..100 {
+            list.insert(0, i);
+        }
+
+        assert_eq!(list.list.len(), 2);
+        assert_eq!(snapshot.get(&0).map(|e| *e.val()), Some(99));
+
+        list.remove(0);
+        drop(snapshot);
+        list.collect_garbage();
+
+        assert!(list.list.is_empty());
+    }
+
+    #[test]
+    fn test_abort_write() {
+        struct Poisoned;
+
+        impl Comparator<i32> for Poisoned {
+            fn compare(&self, a: &i32, b: &i32) -> CmpOrdering {
+                assert!(*a != 13 && *b != 13, "poisoned");
+                a.cmp(b)
+            }
+        }
+
+        let list = VersionedSkipList::with_comparator(Poisoned);
+
+        list.insert(1, "a");
+
+        let result =
+            std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| list.insert(13, "b")));
+        assert!(result.is_err());
+
+        // The aborted write does not hold back the ones after it.
+        assert_eq!(list.insert(2, "c"), 3);
+        assert_eq!(list.seq(), 3);
+        assert!(list.pending.is_empty());
+        assert_eq!(
+            list.snapshot().iter().map(|e| *e.val()).collect::<Vec<_>>(),
+            vec!["a", "c"]
+        );
+    }
+
+    #[test]
+    fn test_sync_snapshot() {
+        use std::sync::Arc;
+        let list = Arc::new(VersionedSkipList::new());
+
+        let writers = (0..4)
+            .map(|t| {
+                let list = list.clone();
+                std::thread::spawn(move || {
+                    for i in 0..1_000 {
+                        list.insert(i % 100, (t, i));
+
+                        if i % 10 == 0 {
+                            list.remove((i + 50) % 100);
+                        }
+                    }
+                })
+            })
+            .collect::<Vec<_>>();
+
+        let readers = (0..4)
+            .map(|_| {
+                let list = list.clone();
+                std::thread::spawn(move || {
+                    let mut last = 0;
+
+                    for _ in 0..100 {
+                        let snapshot = list.snapshot();
+
+                        assert!(snapshot.seq() >= last);
+                        last = snapshot.seq();
+
+                        let first = snapshot
+                            .iter()
+                            .map(|e| (*e.key(), e.seq()))
+                            .collect::<Vec<_>>();
+
+                        assert!(first.iter().all(|(_, seq)| *seq <= snapshot.seq()));
+                        assert!(first.windows(2).all(|w| w[0].0 < w[1].0));
+
+                        // Reading the same snapshot again yields the same state.
+                        let second = snapshot
+                            .iter()
+                            .map(|e| (*e.key(), e.seq()))
+                            .collect::<Vec<_>>();
+                        assert_eq!(first, second);
+                    }
+                })
+            })
+            .collect::<Vec<_>>();
+
+        for thread in writers.into_iter().chain(readers) {
+            thread.join().unwrap()
+        }
+
+        list.collect_garbage();
+
+        // Only the newest version of each key is left, unless it is a tombstone.
+        assert_eq!(list.seq(), 4_400);
+        assert_eq!(list.list.len(), list.snapshot().iter().count());
+    }
+}
@@ -1,14 +1,17 @@
 use haphazard::{Domain, Global, HazardPointer, HazardPointerArray};
 
+use crate::comparator::Comparator;
+
 use core::{
-    ops::{Deref, DerefMut},
+    borrow::Borrow,
+    ops::{Bound, Deref, DerefMut, RangeBounds},
     sync::atomic::AtomicUsize,
 };
 
 mod node;
 mod padded;
 
-pub(crate) use node::{Head, Levels, Node};
+pub(crate) use node::{Head, Node};
 pub(crate) use padded::Padded;
 
 pub(crate) const HEIGHT_BITS: usize = 5;
@@ -19,12 +22,12 @@ pub(crate) const HEIGHT_MASK: usize = (1 << (HEIGHT_BITS + 1)) - 1;
 /// The garbage collection of the list
 /// Utilizes Hazard Pointers under the hood to prevent use-after-frees and
 /// the ABA problem.
-pub(crate) struct Can<'domain> {
-    pub(crate) domain: &'domain Domain<Global>,
-    pub(crate) hp: HazardPointerArray<'domain, Global, 2>,
+pub(crate) struct Can {
+    pub(crate) domain: &'static Domain<Global>,
+    pub(crate) hp: HazardPointerArray<'static, Global, 2>,
 }
 
-impl<'domain> Can<'domain> {
+impl Can {
     pub(crate) fn new() -> Self {
         Can {
             domain: Domain::global(),
@@ -33,7 +36,7 @@ impl<'domain> Can<'domain> {
     }
 }
 
-impl<'domain> Clone for Can<'domain> {
+impl Clone for Can {
     fn clone(&self) -> Self {
         Can {
             domain: self.domain,
@@ -42,14 +45,14 @@ impl<'domain> Clone for Can<'domain> {
     }
 }
 
-impl<'domain> Deref for Can<'domain> {
-    type Target = HazardPointerArray<'domain, Global, 2>;
+impl Deref for Can {
+    type Target = HazardPointerArray<'static, Global, 2>;
     fn deref(&self) -> &Self::Target {
         &self.hp
     }
 }
 
-impl<'domain> DerefMut for Can<'domain> {
+impl DerefMut for Can {
     fn deref_mut(&mut self) -> &mut Self::Target {
         &mut self.hp
     }
@@ -75,29 +78,74 @@ impl ListState {
     }
 }
 
+/// Checks whether `key` does not lie past the end of `range`.
+pub(crate) fn before_end<K, Q, R, C>(comparator: &C, range: &R, key: &K) -> bool
+where
+    K: Borrow<Q>,
+    Q: ?Sized,
+    R: RangeBounds<Q>,
+    C: Comparator<Q>,
+{
+    match range.end_bound() {
+        Bound::Included(end) => comparator.compare(key.borrow(), end).is_le(),
+        Bound::Excluded(end) => comparator.compare(key.borrow(), end).is_lt(),
+        Bound::Unbounded => true,
+    }
+}
+
 /// This macro allows us to define a basic `SkipList`. We only implement the methods that should be
 /// the same for all variations (non-sync, sync, ...) and let the user implement all the other
 /// methods themselves.
+///
+/// Lists that keep the span of each level pass its type as `$span`, see
+/// [Level](node::Level).
 macro_rules! skiplist_basics {
     ($my_list: ident) => {
-        pub struct $my_list<'domain, K, V> {
-            pub(crate) head: core::ptr::NonNull<crate::internal::utils::Head<K, V>>,
+        crate::internal::utils::skiplist_basics!($my_list, ());
+    };
+    ($my_list: ident, $span: ty) => {
+        pub struct $my_list<K, V, C = crate::comparator::OrdComparator> {
+            pub(crate) head: core::ptr::NonNull<crate::internal::utils::Head<K, V, $span>>,
             pub(crate) state: crate::internal::utils::Padded<crate::internal::utils::ListState>,
+            pub(crate) comparator: C,
             #[allow(dead_code)]
-            pub(crate) garbage: crate::internal::utils::Can<'domain>,
+            pub(crate) garbage: crate::internal::utils::Can,
         }
 
-        impl<'domain, K, V> $my_list<'domain, K, V> {
+        impl<K, V> $my_list<K, V> {
             pub fn new() -> Self {
+                Self::with_comparator(crate::comparator::OrdComparator)
+            }
+        }
+
+        impl<K, V, C> $my_list<K, V, C> {
+            /// Creates an empty list whose keys are ordered by `comparator`.
+            pub fn with_comparator(comparator: C) -> Self {
                 $my_list {
                     head: crate::internal::utils::Head::new(),
                     state: crate::internal::utils::Padded::new(
                         crate::internal::utils::ListState::new(),
                     ),
+                    comparator,
                     garbage: crate::internal::utils::Can::new(),
                 }
             }
 
+            pub fn comparator(&self) -> &C {
+                &self.comparator
+            }
+
+            /// Compares `key` to `other` using the [Comparator](crate::comparator::Comparator)
+            /// of the list.
+            fn compare<Q>(&self, key: &K, other: &Q) -> core::cmp::Ordering
+            where
+                K: core::borrow::Borrow<Q>,
+                Q: ?Sized,
+                C: crate::comparator::Comparator<Q>,
+            {
+                crate::comparator::Comparator::compare(&self.comparator, key.borrow(), other)
+            }
+
             pub fn len(&self) -> usize {
                 self.state.len.load(Ordering::Relaxed)
             }
@@ -134,7 +182,7 @@ macro_rules! skiplist_basics {
         }
 
         /// Need this trait for our [Node](Node)s to be generated with random heights.
-        impl<'domain, K, V> GeneratesHeight for $my_list<'domain, K, V> {
+        impl<K, V, C> GeneratesHeight for $my_list<K, V, C> {
             fn gen_height(&self) -> usize {
                 self.gen_height()
             }
@@ -142,12 +190,12 @@ macro_rules! skiplist_basics {
 
         // TODO Verify this is sound for all variants of SkipList
         /// Manual `Drop` implementation for all `SkipList`s
-        impl<'domain, K, V> Drop for $my_list<'domain, K, V> {
+        impl<K, V, C> Drop for $my_list<K, V, C> {
             fn drop(&mut self) {
                 // To ensure this is safe, clear all `HazardPointer`s in the domain.
                 // We do not want to drop a node twice!
                 self.garbage.domain.eager_reclaim();
-                let mut node = unsafe { (*self.head.as_ptr()).levels[0].load_ptr() };
+                let mut node = unsafe { (&(*self.head.as_ptr()).levels)[0].load_ptr() };
 
                 // # Safety
                 //
@@ -155,11 +203,11 @@ macro_rules! skiplist_basics {
                 unsafe {
                     while !node.is_null() {
                         let temp = node;
-                        node = (*temp).levels[0].load_ptr();
-                        crate::internal::utils::Node::<K, V>::drop(temp);
+                        node = (&(*temp).levels)[0].load_ptr();
+                        crate::internal::utils::Node::<K, V, $span>::drop(temp);
                     }
 
-                    crate::internal::utils::Head::<K, V>::drop(self.head);
+                    crate::internal::utils::Head::<K, V, $span>::drop(self.head);
                 }
             }
         }
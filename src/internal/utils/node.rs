@@ -20,16 +20,16 @@ use core::{
 
 /// Head stores the first pointer tower at the beginning of the list. It is always of maximum
 #[repr(C)]
-pub(crate) struct Head<K, V> {
+pub(crate) struct Head<K, V, S = ()> {
     pub(crate) key: K,
     pub(crate) val: V,
     pub(crate) height_and_removed: AtomicUsize,
-    pub(crate) levels: Levels<K, V>,
+    pub(crate) levels: Levels<K, V, S>,
 }
 
-impl<K, V> Head<K, V> {
+impl<K, V, S> Head<K, V, S> {
     pub(crate) fn new() -> NonNull<Self> {
-        let head_ptr = unsafe { Node::<K, V>::alloc(super::HEIGHT).cast() };
+        let head_ptr = unsafe { Node::<K, V, S>::alloc(super::HEIGHT).cast() };
 
         if let Some(head) = NonNull::new(head_ptr) {
             head
@@ -39,16 +39,27 @@ impl<K, V> Head<K, V> {
     }
 
     pub(crate) unsafe fn drop(ptr: NonNull<Self>) {
-        Node::<K, V>::dealloc(ptr.as_ptr().cast());
+        Node::<K, V, S>::dealloc(ptr.as_ptr().cast());
     }
 }
 
 #[repr(C)]
-pub(crate) struct Levels<K, V> {
-    pub(crate) pointers: [MaybeTagged<Node<K, V>>; 1],
+pub(crate) struct Levels<K, V, S = ()> {
+    pub(crate) pointers: [Level<K, V, S>; 1],
 }
 
-impl<K, V> Levels<K, V> {
+/// A single level of a pointer tower.
+///
+/// Only the single-threaded list keeps the span of each level, as an `AtomicUsize`. It is the
+/// number of nodes on the bottom level the pointer skips, counting the node it points to. If the
+/// pointer is null, this is the number of nodes that follow. Other lists leave it as `()`.
+#[repr(C)]
+pub(crate) struct Level<K, V, S = ()> {
+    pub(crate) pointer: MaybeTagged<Node<K, V, S>>,
+    pub(crate) span: S,
+}
+
+impl<K, V, S> Levels<K, V, S> {
     fn get_size(height: usize) -> usize {
         assert!(height <= HEIGHT && height > 0);
 
@@ -56,23 +67,41 @@ impl<K, V> Levels<K, V> {
     }
 }
 
-impl<K, V> Index<usize> for Levels<K, V> {
-    type Output = MaybeTagged<Node<K, V>>;
+impl<K, V> Levels<K, V, AtomicUsize> {
+    pub(crate) fn span(&self, index: usize) -> usize {
+        unsafe {
+            (*self.pointers.as_ptr().add(index))
+                .span
+                .load(Ordering::Relaxed)
+        }
+    }
+
+    pub(crate) fn set_span(&self, index: usize, span: usize) {
+        unsafe {
+            (*self.pointers.as_ptr().add(index))
+                .span
+                .store(span, Ordering::Relaxed)
+        }
+    }
+}
+
+impl<K, V, S> Index<usize> for Levels<K, V, S> {
+    type Output = MaybeTagged<Node<K, V, S>>;
 
     fn index(&self, index: usize) -> &Self::Output {
-        unsafe { self.pointers.get_unchecked(index) }
+        unsafe { &(*self.pointers.as_ptr().add(index)).pointer }
     }
 }
 
 #[repr(C)]
-pub struct Node<K, V> {
+pub struct Node<K, V, S = ()> {
     pub key: K,
     pub val: V,
     pub(crate) height_and_removed: AtomicUsize,
-    pub(crate) levels: Levels<K, V>,
+    pub(crate) levels: Levels<K, V, S>,
 }
 
-impl<K, V> Node<K, V> {
+impl<K, V, S> Node<K, V, S> {
     pub(crate) fn new(key: K, val: V, height: usize) -> *mut Self {
         unsafe {
             let node = Self::alloc(height);
@@ -118,7 +147,7 @@ impl<K, V> Node<K, V> {
     unsafe fn get_layout(height: usize) -> Layout {
         let size_self = mem::size_of::<Self>();
         let align = mem::align_of::<Self>();
-        let size_levels = Levels::<K, V>::get_size(height);
+        let size_levels = Levels::<K, V, S>::get_size(height);
 
         Layout::from_size_align_unchecked(size_self + size_levels, align)
     }
@@ -134,16 +163,18 @@ impl<K, V> Node<K, V> {
         (self.height_and_removed.load(Ordering::Relaxed) & HEIGHT_MASK) as usize
     }
 
+    #[cfg(test)]
     pub(crate) fn refs(&self) -> usize {
         (self.height_and_removed.load(Ordering::SeqCst) & !REMOVED_MASK) >> (HEIGHT_BITS + 1)
     }
 
+    /// Returns the number of references before this one was added.
     pub(crate) fn add_ref(&self) -> usize {
-        let refs = self
+        let old = self
             .height_and_removed
             .fetch_add(1 << (HEIGHT_BITS + 1), Ordering::SeqCst) as usize;
 
-        refs
+        (old & !REMOVED_MASK) >> (HEIGHT_BITS + 1)
     }
 
     pub(crate) fn try_add_ref(&self) -> Result<usize, usize> {
@@ -174,6 +205,20 @@ impl<K, V> Node<K, V> {
             .map(|now| ((now & !REMOVED_MASK) >> (HEIGHT_BITS + 1)) - 1)
     }
 
+    /// Drops the last reference, failing if it is not the only one left.
+    pub(crate) fn try_release(&self) -> Result<(), usize> {
+        self.height_and_removed
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |o| {
+                if (o & !REMOVED_MASK) >> (HEIGHT_BITS + 1) != 1 {
+                    return None;
+                }
+
+                Some(o - (1 << (HEIGHT_BITS + 1)))
+            })
+            .map(|_| ())
+            .map_err(|now| (now & !REMOVED_MASK) >> (HEIGHT_BITS + 1))
+    }
+
     pub(crate) fn removed(&self) -> bool {
         self.height_and_removed
             .load(Ordering::Acquire)
@@ -185,40 +230,47 @@ impl<K, V> Node<K, V> {
         self.set_har_with(|old| old | REMOVED_MASK)
     }
 
+    /// Applies `f` to the state of the node. This only fails if `f` leaves the state unchanged,
+    /// concurrent updates to the reference count are retried.
     fn set_har_with<F>(&self, f: F) -> Result<usize, ()>
     where
         F: Fn(usize) -> usize,
     {
-        let height_and_removed = self.height_and_removed.load(Ordering::SeqCst);
-
-        let new_height_and_removed = f(height_and_removed);
+        self.height_and_removed
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |height_and_removed| {
+                let new_height_and_removed = f(height_and_removed);
 
-        if new_height_and_removed == height_and_removed {
-            return Err(());
-        }
+                if new_height_and_removed == height_and_removed {
+                    return None;
+                }
 
-        // try to exchange
-        self.height_and_removed
-            .compare_exchange(
-                height_and_removed,
-                new_height_and_removed,
-                Ordering::SeqCst,
-                Ordering::SeqCst,
-            )
+                Some(new_height_and_removed)
+            })
             .map_err(|_| ())
     }
 
+    /// Tags the levels of the node from the top, which anyone may help with once the node has
+    /// been marked as removed. Tagging the base level is what takes the node out of the list, so
+    /// this only fails, with the tag found there, if the node was replaced in the meantime.
     pub(crate) fn tag_levels(&self, tag: usize) -> Result<usize, usize> {
-        for level in (0..self.height()).rev() {
-            if let Err(o_tag) = self.levels[level].compare_exchange_tag(0, tag) {
-                return Err(o_tag);
-            }
+        for level in (1..self.height()).rev() {
+            let _ = self.levels[level].compare_exchange_tag(0, tag);
+        }
+
+        match self.levels[0].compare_exchange_tag(0, tag) {
+            Ok(_) => Ok(self.height() - 1),
+            Err(o_tag) if o_tag == tag => Ok(self.height() - 1),
+            Err(o_tag) => Err(o_tag),
         }
-        Ok(self.height() - 1)
     }
 
+    /// Marks the node as removed and tags its levels. If another thread marked it first, we help
+    /// tagging it instead, so that searches move past it.
     pub(crate) fn try_remove_and_tag(&self) -> Result<(), ()> {
-        self.set_removed()?;
+        if self.set_removed().is_err() {
+            let _ = self.tag_levels(1);
+            return Err(());
+        }
 
         self.tag_levels(1).map_err(|_| ())?;
 
@@ -226,7 +278,7 @@ impl<K, V> Node<K, V> {
     }
 }
 
-impl<K, V> PartialEq for Node<K, V>
+impl<K, V, S> PartialEq for Node<K, V, S>
 where
     K: PartialEq,
     V: PartialEq,
@@ -236,7 +288,7 @@ where
     }
 }
 
-impl<K, V> Debug for Node<K, V>
+impl<K, V, S> Debug for Node<K, V, S>
 where
     K: Debug,
     V: Debug,
@@ -256,7 +308,7 @@ where
     }
 }
 
-impl<K, V> Display for Node<K, V>
+impl<K, V, S> Display for Node<K, V, S>
 where
     K: Debug,
     V: Debug,
@@ -272,13 +324,14 @@ where
     }
 }
 
+#[cfg(test)]
 mod test {
     use super::*;
 
     #[test]
     fn test_removed() {
         unsafe {
-            let node = Node::new(1, (), 3);
+            let node: *mut Node<_, _> = Node::new(1, (), 3);
 
             assert!(!(*node).removed());
 
@@ -293,4 +346,14 @@ mod test {
             assert_eq!((*node).try_add_ref().unwrap(), 2);
         }
     }
+
+    #[test]
+    fn test_level_size() {
+        // Only the levels of the single-threaded list pay for a span.
+        assert_eq!(mem::size_of::<Level<u8, u8>>(), mem::size_of::<usize>());
+        assert_eq!(
+            mem::size_of::<Level<u8, u8, AtomicUsize>>(),
+            2 * mem::size_of::<usize>()
+        );
+    }
 }
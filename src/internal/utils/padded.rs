@@ -41,10 +41,6 @@ impl<T> Padded<T> {
     pub(crate) const fn new(t: T) -> Self {
         Padded(t)
     }
-
-    pub(crate) fn into_inner(self) -> T {
-        self.0
-    }
 }
 
 impl<T> Deref for Padded<T> {
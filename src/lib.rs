@@ -7,9 +7,13 @@
     unreachable_pub
 )]
 pub mod collections;
+pub mod comparator;
 pub mod internal;
 pub mod skiplist;
 
 pub use collections::priority_queue::PriorityQueue;
+pub use collections::set::{SkipSet, SyncSkipSet};
+pub use comparator::{Comparator, OrdComparator, ReverseComparator};
 pub use internal::skiplist::SkipList;
 pub use internal::sync::SkipList as SyncSkipList;
+pub use internal::sync::VersionedSkipList;
@@ -1,20 +1,54 @@
+use core::ops::RangeBounds;
+
+/// The interface shared by the [SkipList](crate::SkipList) and the
+/// [SyncSkipList](crate::SyncSkipList), which allows code to be generic over both.
+///
+/// Methods that modify the list take `&mut self`, as that is what the single-threaded list
+/// requires. The concurrent list also offers them on shared references.
 pub trait SkipList<K, V> {
+    /// A reference to an entry that is part of the list.
     type Entry<'a>: Entry<'a, K, V>
     where
         Self: 'a;
 
+    /// An entry that has been taken out of the list, either because it was removed or replaced.
+    type Removed<'a>: Entry<'a, K, V>
+    where
+        Self: 'a;
+
+    type Iter<'a>: Iterator<Item = Self::Entry<'a>>
+    where
+        Self: 'a;
+
+    type Range<'a, R>: Iterator<Item = Self::Entry<'a>>
+    where
+        Self: 'a,
+        R: RangeBounds<K> + 'a;
+
     fn new() -> Self;
 
-    fn insert(&self, key: K, value: V) -> Option<V>;
+    /// Inserts `value` for `key`. If the list already contained `key`, the replaced entry is
+    /// returned.
+    fn insert(&mut self, key: K, value: V) -> Option<Self::Removed<'_>>;
 
     fn get<'a>(&'a self, key: &K) -> Option<Self::Entry<'a>>;
 
-    fn remove(&self, key: &K) -> Option<(K, V)>;
+    fn contains_key(&self, key: &K) -> bool {
+        self.get(key).is_some()
+    }
+
+    fn remove(&mut self, key: &K) -> Option<Self::Removed<'_>>;
 
     fn front<'a>(&'a self) -> Option<Self::Entry<'a>>;
 
     fn last<'a>(&'a self) -> Option<Self::Entry<'a>>;
 
+    fn iter<'a>(&'a self) -> Self::Iter<'a>;
+
+    fn range<'a, R>(&'a self, range: R) -> Self::Range<'a, R>
+    where
+        R: RangeBounds<K> + 'a;
+
     fn len(&self) -> usize;
 
     fn is_empty(&self) -> bool {
@@ -26,3 +60,57 @@ pub trait Entry<'a, K, V> {
     fn val(&self) -> &V;
     fn key(&self) -> &K;
 }
+
+/// Entries taken out of the single-threaded list are owned.
+impl<'a, K, V> Entry<'a, K, V> for (K, V) {
+    fn val(&self) -> &V {
+        &self.1
+    }
+
+    fn key(&self) -> &K {
+        &self.0
+    }
+}
+
+#[cfg(test)]
+mod trait_test {
+    use super::{Entry, SkipList};
+
+    fn exercise<L>()
+    where
+        L: SkipList<u32, &'static str>,
+    {
+        let mut list = L::new();
+
+        assert!(list.insert(2, "b").is_none());
+        assert!(list.insert(1, "a").is_none());
+        assert!(list.insert(3, "c").is_none());
+
+        assert_eq!(list.insert(2, "d").map(|e| *e.val()), Some("b"));
+        assert_eq!(list.len(), 3);
+        assert_eq!(list.get(&2).map(|e| *e.val()), Some("d"));
+        assert!(list.contains_key(&1));
+        assert_eq!(list.front().map(|e| *e.key()), Some(1));
+        assert_eq!(list.last().map(|e| *e.key()), Some(3));
+
+        assert_eq!(
+            list.iter().map(|e| *e.key()).collect::<Vec<_>>(),
+            vec![1, 2, 3]
+        );
+        assert_eq!(
+            list.range(2..).map(|e| *e.val()).collect::<Vec<_>>(),
+            vec!["d", "c"]
+        );
+
+        assert_eq!(list.remove(&1).map(|e| *e.key()), Some(1));
+        assert!(list.remove(&1).is_none());
+        assert_eq!(list.len(), 2);
+        assert!(!list.is_empty());
+    }
+
+    #[test]
+    fn test_implementations() {
+        exercise::<crate::SkipList<u32, &'static str>>();
+        exercise::<crate::SyncSkipList<u32, &'static str>>();
+    }
+}